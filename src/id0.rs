@@ -2,6 +2,7 @@ use std::collections::HashMap;
 use std::num::NonZeroU32;
 use std::ops::Range;
 
+use crate::id1::{ByteType, ID1Section};
 use crate::ida_reader::{IdaGenericUnpack, IdaUnpack, IdaUnpacker};
 use crate::{til, IDBHeader, IDBSectionCompression};
 
@@ -18,10 +19,14 @@ pub use address_info::*;
 mod dirtree;
 pub use dirtree::*;
 
+/// A region of the database that was loaded from the input file
 #[derive(Clone, Debug)]
 pub struct IDBFileRegions {
+    /// first address of the region
     pub start: u64,
+    /// address after the last byte of the region
     pub end: u64,
+    /// offset of the region relative to the image base, AKA the RVA
     pub eva: u64,
 }
 
@@ -59,6 +64,109 @@ impl IDBFileRegions {
     }
 }
 
+/// A relocation applied by the loader, from the `$ fixups` netnode
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Fixup {
+    /// address of the relocated value
+    pub address: u64,
+    pub fixup_type: FixupType,
+    /// raw `FIXUPF_*` flags
+    pub flags: u32,
+    /// selector of the target segment
+    pub sel: u64,
+    /// the target offset
+    pub offset: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FixupType {
+    /// 32-bit offset
+    Off32,
+    /// 64-bit offset
+    Off64,
+    /// `FIXUP_*` value not modeled, including custom fixups
+    Other(u16),
+}
+
+impl FixupType {
+    fn from_raw(value: u16) -> Self {
+        match value {
+            4 => Self::Off32,
+            12 => Self::Off64,
+            value => Self::Other(value),
+        }
+    }
+
+    /// size in bytes of the relocated value, if known
+    pub fn size(self) -> Option<u8> {
+        match self {
+            Self::Off32 => Some(4),
+            Self::Off64 => Some(8),
+            Self::Other(_) => None,
+        }
+    }
+}
+
+impl Fixup {
+    fn read(key: &[u8], data: &[u8], is_64: bool) -> Result<Self> {
+        let address = parse_number(key, true, is_64)
+            .ok_or_else(|| anyhow!("Invalid Fixup address"))?;
+        let mut input = IdaUnpacker::new(data, is_64);
+        // TODO the meaning of the lowest bit is unknown
+        let type_raw = input.unpack_dw()?;
+        #[cfg(feature = "restrictive")]
+        ensure!(type_raw & 1 == 0, "Unknown Fixup type {type_raw:#x}");
+        let fixup_type = FixupType::from_raw(type_raw >> 1);
+        let flags = input.unpack_dd()?;
+        let sel = input.unpack_usize()?;
+        let offset = input.unpack_usize()?;
+        // TODO the displacement may follow with some flags
+        #[cfg(feature = "restrictive")]
+        ensure!(input.inner().is_empty(), "Unparsed data in Fixup");
+        Ok(Self {
+            address,
+            fixup_type,
+            flags,
+            sel,
+            offset,
+        })
+    }
+
+    /// the address referenced by the fixup, read from the current value of
+    /// the relocated bytes, only for [FixupType::Off32] and
+    /// [FixupType::Off64]
+    ///
+    /// NOTE the value is read as little-endian
+    pub fn resolve(&self, id1: &ID1Section) -> Option<u64> {
+        let size = self.fixup_type.size()?;
+        let mut bytes = [0u8; 8];
+        for (i, byte) in bytes[..size.into()].iter_mut().enumerate() {
+            let address = self.address.checked_add(i as u64)?;
+            *byte = id1.byte_info(address)?.value()?;
+        }
+        Some(u64::from_le_bytes(bytes))
+    }
+}
+
+/// The index of the special netnodes of the database, if they exist
+#[derive(Clone, Copy, Debug)]
+pub struct ID0SpecialNodes {
+    /// `Root Node`
+    pub root_node: Option<u64>,
+    /// `$ segs`
+    pub segs: Option<u64>,
+    /// `$ segstrings`
+    pub segstrings: Option<u64>,
+    /// `$ loader name`
+    pub loader_name: Option<u64>,
+    /// `$ fileregions`
+    pub fileregions: Option<u64>,
+    /// `$ funcs`
+    pub funcs: Option<u64>,
+    /// `$ entry points`
+    pub entry_points: Option<u64>,
+}
+
 #[derive(Clone, Debug)]
 pub enum FunctionsAndComments<'a> {
     // It's just the name "$ funcs"
@@ -69,7 +177,12 @@ pub enum FunctionsAndComments<'a> {
 }
 
 impl<'a> FunctionsAndComments<'a> {
-    fn read(key: &'a [u8], value: &'a [u8], is_64: bool) -> Result<Self> {
+    fn read(
+        key: &'a [u8],
+        value: &'a [u8],
+        version: u16,
+        is_64: bool,
+    ) -> Result<Self> {
         let [key_type, sub_key @ ..] = key else {
             return Err(anyhow!("invalid Funcs subkey"));
         };
@@ -78,9 +191,8 @@ impl<'a> FunctionsAndComments<'a> {
                 ensure!(parse_maybe_cstr(value) == Some(&b"$ funcs"[..]));
                 Ok(Self::Name)
             }
-            b'S' => {
-                IDBFunction::read(sub_key, value, is_64).map(Self::Function)
-            }
+            b'S' => IDBFunction::read(sub_key, value, version, is_64)
+                .map(Self::Function),
             // some kind of style setting, maybe setting font and background color
             b'R' | b'C' if value.starts_with(&[4, 3, 2, 1]) => {
                 Ok(Self::Unknown { key, value })
@@ -116,10 +228,80 @@ impl<'a> FunctionsAndComments<'a> {
 #[derive(Clone, Debug)]
 pub struct IDBFunction {
     pub address: Range<u64>,
+    /// the raw function flags, see [IDBFunction::flags]
     pub flags: u16,
     pub extra: Option<IDBFunctionExtra>,
 }
 
+#[derive(Clone, Copy, Debug)]
+pub struct IDBFunctionFlags(u16);
+impl IDBFunctionFlags {
+    pub fn into_raw(self) -> u16 {
+        self.0
+    }
+
+    /// Function doesn't return
+    pub fn is_noret(&self) -> bool {
+        self.0 & 0x0001 != 0
+    }
+    /// Far function
+    pub fn is_far(&self) -> bool {
+        self.0 & 0x0002 != 0
+    }
+    /// Library function
+    pub fn is_lib(&self) -> bool {
+        self.0 & 0x0004 != 0
+    }
+    /// Static function
+    pub fn is_static(&self) -> bool {
+        self.0 & 0x0008 != 0
+    }
+    /// Function uses frame pointer (BP)
+    pub fn is_frame(&self) -> bool {
+        self.0 & 0x0010 != 0
+    }
+    /// User has specified far-ness of the function
+    pub fn is_user_far(&self) -> bool {
+        self.0 & 0x0020 != 0
+    }
+    /// A hidden function chunk
+    pub fn is_hidden(&self) -> bool {
+        self.0 & 0x0040 != 0
+    }
+    /// Thunk (jump) function
+    pub fn is_thunk(&self) -> bool {
+        self.0 & 0x0080 != 0
+    }
+    /// BP points to the bottom of the stack frame
+    pub fn is_bottom_bp(&self) -> bool {
+        self.0 & 0x0100 != 0
+    }
+    /// Function 'non-return' analysis must be performed
+    pub fn is_noret_pending(&self) -> bool {
+        self.0 & 0x0200 != 0
+    }
+    /// SP-analysis has been performed
+    pub fn is_sp_ready(&self) -> bool {
+        self.0 & 0x0400 != 0
+    }
+    /// Function changes SP in untraceable way
+    pub fn is_fuzzy_sp(&self) -> bool {
+        self.0 & 0x0800 != 0
+    }
+    /// Prolog analysis has been performed by last SP-analysis
+    pub fn is_prolog_ok(&self) -> bool {
+        self.0 & 0x1000 != 0
+    }
+    /// 'argsize' field has been validated
+    pub fn is_purged_ok(&self) -> bool {
+        self.0 & 0x4000 != 0
+    }
+    /// This is a function tail
+    pub fn is_tail(&self) -> bool {
+        self.0 & 0x8000 != 0
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum IDBFunctionExtra {
     NonTail {
@@ -133,16 +315,30 @@ pub enum IDBFunctionExtra {
 }
 
 impl IDBFunction {
+    /// the function flags
+    pub fn flags(&self) -> IDBFunctionFlags {
+        IDBFunctionFlags(self.flags)
+    }
+
     // InnerRef 5c1b89aa-5277-4c98-98f6-cec08e1946ec 0x28f810
-    fn read(_key: &[u8], value: &[u8], is_64: bool) -> Result<Self> {
+    fn read(
+        _key: &[u8],
+        value: &[u8],
+        version: u16,
+        is_64: bool,
+    ) -> Result<Self> {
         let mut input = IdaUnpacker::new(value, is_64);
         let address = input.unpack_address_range()?;
         let flags = input.unpack_dw()?;
 
-        // CONST migrate this to mod flags
-        const FUNC_TAIL: u16 = 0x8000;
-        let extra = if flags & FUNC_TAIL != 0 {
-            Self::read_extra_tail(input, address.start).ok()
+        let extra = if IDBFunctionFlags(flags).is_tail() {
+            let extra =
+                Self::read_extra_tail(input, address.start, flags, version);
+            #[cfg(feature = "restrictive")]
+            let extra = Some(extra?);
+            #[cfg(not(feature = "restrictive"))]
+            let extra = extra.ok();
+            extra
         } else {
             Self::read_extra_regular(input).ok()
         };
@@ -169,23 +365,71 @@ impl IDBFunction {
     }
 
     fn read_extra_tail(
-        mut input: impl IdaUnpack,
+        mut input: IdaUnpacker<&[u8]>,
         address_start: u64,
+        flags: u16,
+        version: u16,
     ) -> Result<IDBFunctionExtra> {
-        // offset of the function owner in relation to the function start
-        let owner_offset = input.unpack_usize()? as i64;
-        let owner = match address_start.checked_add_signed(owner_offset) {
+        // offset of the function start, subtracted from the tail start
+        let owner_offset = if input.is_64() {
+            input.unpack_dq()? as i64
+        } else {
+            (input.unpack_dd()? as i32).into()
+        };
+        let owner = match address_start
+            .checked_add_signed(owner_offset.wrapping_neg())
+        {
             Some(0xFFFF_FFFF) => u64::MAX,
             Some(value) => value,
             None => return Err(anyhow!("Owner Function offset is invalid")),
         };
-        let refqty = input.unpack_usize_ext_max()?;
-        let _unknown1 = input.unpack_dw()?;
-        let _unknown2 = input.unpack_usize_ext_max()?;
+        // TODO detect versions with more accuracy, 7.0 don't have the
+        // unknown value and 8.3 does
+        let refqty = match version {
+            // NOTE no samples, this is the layout that was used for all
+            // versions, it was never verified
+            ..=699 => {
+                let refqty = input.unpack_usize_ext_max()?;
+                let _unknown1 = input.unpack_dw()?;
+                let _unknown2 = input.unpack_usize_ext_max()?;
+                refqty
+            }
+            700..=799 => {
+                let refqty = input.unpack_dd()?;
+                Self::read_tail_flags(&mut input, flags)?;
+                refqty.into()
+            }
+            800.. => {
+                let _unknown = input.unpack_dd()?;
+                #[cfg(feature = "restrictive")]
+                ensure!(
+                    _unknown == 0,
+                    "Unknown function tail value {_unknown}"
+                );
+                let refqty = input.unpack_dd()?;
+                Self::read_tail_flags(&mut input, flags)?;
+                refqty.into()
+            }
+        };
         // TODO make data depending on variables that I don't understant
         // InnerRef 5c1b89aa-5277-4c98-98f6-cec08e1946ec 0x28fa93
+        ensure!(input.inner().is_empty(), "Unparsed data in function tail");
         Ok(IDBFunctionExtra::Tail { owner, refqty })
     }
+
+    /// the full function flags, the first 16 bits are the same as the
+    /// chunk flags
+    fn read_tail_flags(
+        input: &mut IdaUnpacker<&[u8]>,
+        flags: u16,
+    ) -> Result<()> {
+        let full_flags = input.unpack_dq()?;
+        ensure!(
+            full_flags & 0xFFFF == u64::from(flags),
+            "Function tail flags {full_flags:X} don't match {flags:X}"
+        );
+        Ok(())
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -248,7 +492,7 @@ impl<'a> EntryPointRaw<'a> {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct EntryPoint {
     pub name: String,
     pub address: u64,
@@ -273,7 +517,9 @@ pub(crate) fn parse_number(
 }
 
 // parse a string that maybe is finalized with \x00
-fn parse_maybe_cstr(data: &[u8]) -> Option<&[u8]> {
+/// strip the trailing `\x00` of a c-string, return `None` if there is any
+/// non-zero byte after the first `\x00`
+pub fn parse_maybe_cstr(data: &[u8]) -> Option<&[u8]> {
     // find the end of the string
     let end_pos = data.iter().position(|b| *b == 0).unwrap_or(data.len());
     // make sure there is no data after the \x00
@@ -1,8 +1,9 @@
 use anyhow::{anyhow, Result};
 
-use crate::til;
+use crate::id1::{ByteType, ID1Section};
+use crate::til::{self, section::TILSection};
 
-use super::{parse_maybe_cstr, ID0Entry, IDBFileRegions};
+use super::{parse_maybe_cstr, ID0Entry, IDBFileRegions, Id0TilOrd};
 
 #[derive(Clone, Debug)]
 pub enum AddressInfo<'a> {
@@ -12,6 +13,37 @@ pub enum AddressInfo<'a> {
     Other { key: &'a [u8], value: &'a [u8] },
 }
 
+impl AddressInfo<'_> {
+    /// The type applied at this address, if any. References to other types
+    /// are solved using the TIL section
+    pub fn applied_type<'b>(
+        &'b self,
+        til: &'b TILSection,
+    ) -> Option<Result<&'b til::Type>> {
+        let AddressInfo::TilType(ty) = self else {
+            return None;
+        };
+        let til::TypeVariant::Typeref(typeref) = &ty.type_variant else {
+            return Some(Ok(ty));
+        };
+        let ref_ty = match &typeref.typeref_value {
+            til::TyperefValue::Ref(idx) => til.types.get(*idx),
+            til::TyperefValue::UnsolvedName(Some(name)) => {
+                til.get_name(name.as_bytes())
+            }
+            til::TyperefValue::UnsolvedOrd(ord) => {
+                til.get_ord(Id0TilOrd { ord: (*ord).into() })
+            }
+            til::TyperefValue::UnsolvedName(None) => return Some(Ok(ty)),
+        };
+        Some(
+            ref_ty
+                .map(|ref_ty| &ref_ty.tinfo)
+                .ok_or_else(|| anyhow!("Unable to find the applied type")),
+        )
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum Comments<'a> {
     Comment(&'a [u8]),
@@ -51,6 +83,16 @@ impl<'a, I: Iterator<Item = Result<IDBFileRegions>>>
             current_region: AddressInfoIter::new(&[], is_64),
         }
     }
+
+    /// only include addresses with the byte type, according to the `ID1`
+    pub fn with_filter(
+        mut self,
+        id1: &'a ID1Section,
+        byte_type: ByteType,
+    ) -> Self {
+        self.current_region.filter = Some((id1, byte_type));
+        self
+    }
 }
 
 impl<'a, I: Iterator<Item = Result<IDBFileRegions>> + 'a> Iterator
@@ -69,21 +111,24 @@ impl<'a, I: Iterator<Item = Result<IDBFileRegions>> + 'a> Iterator
                 Some(Err(err)) => return Some(Err(err)),
             };
             let is_64 = self.current_region.is_64;
-            let start_key: Vec<u8> =
-                crate::id0::key_from_address(region.start, is_64).collect();
-            let end_key: Vec<u8> =
-                crate::id0::key_from_address(region.end, is_64).collect();
+            let (start_key, end_key) = match (
+                crate::id0::key_from_address(region.start, is_64),
+                crate::id0::key_from_address(region.end, is_64),
+            ) {
+                (Ok(start_key), Ok(end_key)) => (start_key, end_key),
+                (Err(err), _) | (_, Err(err)) => return Some(Err(err)),
+            };
             let start = self
                 .all_entries
-                .binary_search_by_key(&&start_key[..], |b| &b.key[..])
+                .binary_search_by_key(&start_key.as_ref(), |b| &b.key[..])
                 .unwrap_or_else(|start| start);
             let end = self
                 .all_entries
-                .binary_search_by_key(&&end_key[..], |b| &b.key[..])
+                .binary_search_by_key(&end_key.as_ref(), |b| &b.key[..])
                 .unwrap_or_else(|end| end);
 
             let entries = &self.all_entries[start..end];
-            self.current_region = AddressInfoIter::new(entries, is_64);
+            self.current_region.entries = entries;
             // try again using this new region
             return self.next();
         };
@@ -94,11 +139,43 @@ impl<'a, I: Iterator<Item = Result<IDBFileRegions>> + 'a> Iterator
 pub(crate) struct AddressInfoIter<'a> {
     entries: &'a [ID0Entry],
     is_64: bool,
+    filter: Option<(&'a ID1Section, ByteType)>,
 }
 
 impl<'a> AddressInfoIter<'a> {
     pub fn new(entries: &'a [ID0Entry], is_64: bool) -> Self {
-        Self { entries, is_64 }
+        Self {
+            entries,
+            is_64,
+            filter: None,
+        }
+    }
+}
+
+impl AddressInfoIter<'_> {
+    /// skip all the entries of addresses that don't have the byte type
+    fn skip_filtered(&mut self, id1: &ID1Section, byte_type: ByteType) {
+        let key_start = if self.is_64 { 9 } else { 5 };
+        while let Some(first) = self.entries.first() {
+            let address = first
+                .key
+                .get(1..key_start)
+                .and_then(|addr| super::parse_number(addr, true, self.is_64));
+            let Some(address) = address else {
+                // let the iterator report the invalid entry
+                return;
+            };
+            if id1.byte_type(address) == Some(byte_type) {
+                return;
+            }
+            let addr_key = &first.key[..key_start];
+            let skip = self
+                .entries
+                .iter()
+                .position(|entry| !entry.key.starts_with(addr_key))
+                .unwrap_or(self.entries.len());
+            self.entries = &self.entries[skip..];
+        }
     }
 }
 
@@ -106,6 +183,9 @@ impl<'a> Iterator for AddressInfoIter<'a> {
     type Item = Result<(u64, AddressInfo<'a>)>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if let Some((id1, byte_type)) = self.filter {
+            self.skip_filtered(id1, byte_type);
+        }
         let (current, rest) = self.entries.split_first()?;
         self.entries = rest;
         let value = &current.value[..];
@@ -140,20 +220,20 @@ impl<'a> Iterator for AddressInfoIter<'a> {
                 let Some(comment) = parse_maybe_cstr(value) else {
                     return Some(Err(anyhow!("Post-Comment is not valid CStr")));
                 };
-                Some(Ok((address, AddressInfo::Comment(Comments::PreComment(comment)))))
+                Some(Ok((address, AddressInfo::Comment(Comments::PostComment(comment)))))
             },
             (b'S', Some(0x0)) => {
                 let Some(comment) = parse_maybe_cstr(value) else {
                     return Some(Err(anyhow!("Comment is not valid CStr")));
                 };
-                Some(Ok((address, AddressInfo::Comment(Comments::PreComment(comment)))))
+                Some(Ok((address, AddressInfo::Comment(Comments::Comment(comment)))))
             },
             // Repeatable comment
             (b'S', Some(0x1)) => {
                 let Some(comment) = parse_maybe_cstr(value) else {
                     return Some(Err(anyhow!("Repeatable Comment is not valid CStr")));
                 };
-                Some(Ok((address, AddressInfo::Comment(Comments::PreComment(comment)))))
+                Some(Ok((address, AddressInfo::Comment(Comments::RepeatableComment(comment)))))
             },
 
             // Type at this address
@@ -1,8 +1,11 @@
+use std::borrow::Cow;
 use std::{ffi::CStr, io::Read};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 
 use crate::ida_reader::{IdaGenericBufUnpack, IdaGenericUnpack};
+use crate::nam::NamSection;
+use crate::{IDBString, ParseReport, Warning};
 
 use super::*;
 
@@ -96,6 +99,15 @@ impl ID0Header {
 pub struct ID0Section {
     is_64: bool,
     pub entries: Vec<ID0Entry>,
+    skipped_pages: Vec<ID0SkippedPage>,
+    reordered: bool,
+}
+
+/// B-tree page that could not be read and was left out of the section
+#[derive(Debug, Clone)]
+pub struct ID0SkippedPage {
+    pub page: u32,
+    pub error: String,
 }
 
 #[derive(Debug, Clone)]
@@ -141,6 +153,8 @@ impl ID0Section {
             return Ok(Self {
                 is_64: idb_header.magic_version.is_64(),
                 entries: vec![],
+                skipped_pages: vec![],
+                reordered: false,
             });
         };
 
@@ -148,6 +162,8 @@ impl ID0Section {
         let mut pages =
             HashMap::with_capacity(header.page_count.try_into().unwrap());
         let mut pending_pages = vec![root_page];
+        #[cfg_attr(feature = "restrictive", allow(unused_mut))]
+        let mut skipped_pages = vec![];
         loop {
             if pending_pages.is_empty() {
                 break;
@@ -157,13 +173,25 @@ impl ID0Section {
             if pages.contains_key(&page_idx) {
                 continue;
             }
-            // read the full page
-            ensure!((page_idx.get() as usize) < pages_in_section);
-            let page_offset =
-                page_idx.get() as usize * header.page_size as usize;
-            let page_raw =
-                &input[page_offset..page_offset + header.page_size as usize];
-            let page = ID0Page::read(page_raw, &header)?;
+            let page = match Self::read_page(
+                input,
+                &header,
+                page_idx,
+                pages_in_section,
+            ) {
+                Ok(page) => page,
+                #[cfg(feature = "restrictive")]
+                Err(error) => return Err(error),
+                // skip the page and all it's children, keep the remaining
+                #[cfg(not(feature = "restrictive"))]
+                Err(error) => {
+                    skipped_pages.push(ID0SkippedPage {
+                        page: page_idx.get(),
+                        error: format!("{error:?}"),
+                    });
+                    continue;
+                }
+            };
             // put in the queue the pages that need parsing, AKA children of this page
             match &page {
                 ID0Page::Index { preceding, entries } => {
@@ -190,27 +218,59 @@ impl ID0Section {
             Vec::with_capacity(header.record_count.try_into().unwrap());
         Self::tree_to_vec(root_page, &mut pages, &mut entries);
 
-        // make sure the vector is sorted
-        ensure!(entries.windows(2).all(|win| {
-            let [a, b] = win else { unreachable!() };
-            a.key < b.key
-        }));
+        // make sure the vector is sorted, all the lookups depend on it
+        let reordered = !Self::is_sorted(&entries);
+        #[cfg(feature = "restrictive")]
+        ensure!(!reordered, "ID0 B-tree entries are out of order");
+        #[cfg(not(feature = "restrictive"))]
+        if reordered {
+            Self::sort_and_dedup(&mut entries);
+        }
+        debug_assert!(Self::is_sorted(&entries));
 
         // make sure the right number of entries are in the final vector
-        ensure!(entries.len() == header.record_count.try_into().unwrap());
+        ensure!(
+            !skipped_pages.is_empty()
+                || reordered
+                || entries.len() == header.record_count.try_into().unwrap()
+        );
 
         Ok(ID0Section {
             is_64: idb_header.magic_version.is_64(),
             entries,
+            skipped_pages,
+            reordered,
         })
     }
 
+    fn read_page(
+        input: &[u8],
+        header: &ID0Header,
+        page_idx: NonZeroU32,
+        pages_in_section: usize,
+    ) -> Result<ID0Page> {
+        // read the full page
+        ensure!(
+            (page_idx.get() as usize) < pages_in_section,
+            "Invalid ID0 page index {page_idx}"
+        );
+        let page_offset = page_idx.get() as usize * header.page_size as usize;
+        let page_raw =
+            &input[page_offset..page_offset + header.page_size as usize];
+        ID0Page::read(page_raw, header)
+            .with_context(|| format!("Unable to read ID0 page {page_idx}"))
+    }
+
     fn tree_to_vec(
         page_idx: NonZeroU32,
         pages: &mut HashMap<NonZeroU32, ID0Page>,
         output: &mut Vec<ID0Entry>,
     ) {
-        match pages.remove(&page_idx).unwrap() {
+        // pages that failed to parse are not included
+        let Some(page) = pages.remove(&page_idx) else {
+            return;
+        };
+        match page {
             ID0Page::Index { preceding, entries } => {
                 if let Some(preceding) = preceding {
                     // if not root, add the preceding page before this one
@@ -227,6 +287,42 @@ impl ID0Section {
         }
     }
 
+    /// true if some B-tree pages were skipped, so entries could be missing
+    pub fn is_partial(&self) -> bool {
+        !self.skipped_pages.is_empty()
+    }
+
+    /// B-tree pages that were not read, only possible without `restrictive`
+    pub fn skipped_pages(&self) -> &[ID0SkippedPage] {
+        &self.skipped_pages
+    }
+
+    /// true if the B-tree entries were out of order and had to be sorted,
+    /// only possible without `restrictive`
+    pub fn is_reordered(&self) -> bool {
+        self.reordered
+    }
+
+    /// sort the entries by key, removing duplicated keys. All the lookups
+    /// require the entries to be sorted, this is only necessary after
+    /// modifying [ID0Section::entries] directly
+    pub fn sort_entries(&mut self) {
+        Self::sort_and_dedup(&mut self.entries);
+    }
+
+    fn sort_and_dedup(entries: &mut Vec<ID0Entry>) {
+        // stable, so the first of the duplicated entries is kept
+        entries.sort_by(|a, b| a.key.cmp(&b.key));
+        entries.dedup_by(|b, a| a.key == b.key);
+    }
+
+    fn is_sorted(entries: &[ID0Entry]) -> bool {
+        entries.windows(2).all(|win| {
+            let [a, b] = win else { unreachable!() };
+            a.key < b.key
+        })
+    }
+
     pub fn all_entries(&self) -> impl Iterator<Item = &ID0Entry> {
         self.entries.iter()
     }
@@ -250,6 +346,7 @@ impl ID0Section {
         })
     }
 
+    /// get the entry with exactly this raw key, if any
     pub fn get(&self, key: impl AsRef<[u8]>) -> Option<&ID0Entry> {
         self.binary_search(key).ok().map(|i| &self.entries[i])
     }
@@ -266,6 +363,7 @@ impl ID0Section {
         self.entries[start..end].iter()
     }
 
+    /// all the entries with keys starting with this raw key
     pub fn sub_values(
         &self,
         key: impl AsRef<[u8]>,
@@ -277,6 +375,94 @@ impl ID0Section {
         self.entries[start..end].iter()
     }
 
+    /// find the netnode index with the name, if any
+    pub fn netnode_idx_by_name(&self, name: &str) -> Result<Option<u64>> {
+        let key: Vec<u8> =
+            b"N".iter().chain(name.as_bytes()).copied().collect();
+        self.get(key)
+            .map(|entry| parse_node_value(&entry.value))
+            .transpose()
+    }
+
+    /// find the index of all the special netnodes at once
+    pub fn special_nodes(&self) -> Result<ID0SpecialNodes> {
+        Ok(ID0SpecialNodes {
+            root_node: self.netnode_idx_by_name("Root Node")?,
+            segs: self.netnode_idx_by_name("$ segs")?,
+            segstrings: self.netnode_idx_by_name("$ segstrings")?,
+            loader_name: self.netnode_idx_by_name("$ loader name")?,
+            fileregions: self.netnode_idx_by_name("$ fileregions")?,
+            funcs: self.netnode_idx_by_name("$ funcs")?,
+            entry_points: self.netnode_idx_by_name("$ entry points")?,
+        })
+    }
+
+    /// upgrade step `0x40` of [ID0Section::database_upgrade_flags]
+    ///
+    /// TODO the meaning is unknown, it's set in all known databases, including
+    /// the ones created with IDA 7.0 or newer
+    pub const UPGRADE_700_0X40: u64 = 0x40;
+    /// upgrade step `0x10000` of [ID0Section::database_upgrade_flags]
+    ///
+    /// TODO the meaning is unknown, it's set in all known databases, including
+    /// the ones created with IDA 7.0 or newer
+    pub const UPGRADE_700_0X10000: u64 = 0x10000;
+
+    /// read the `$ upgrade 700` flags, each bit is an upgrade step applied
+    /// when the database was migrated to the IDA 7.0 format, if any
+    ///
+    /// NOTE databases created with IDA 7.0 or newer also have these flags,
+    /// with the steps known by the version that created it set, see
+    /// [ID0Section::UPGRADE_700_0X40] and [ID0Section::UPGRADE_700_0X10000]
+    pub fn database_upgrade_flags(&self) -> Result<Option<u64>> {
+        let Some(entry) = self.get("N$ upgrade 700") else {
+            return Ok(None);
+        };
+        let key = NetnodeKey::from_node_value(&entry.value, self.is_64)?
+            .with_tag(b'A')
+            .with_index(0)?;
+        self.get(key)
+            .map(|entry| parse_node_value(&entry.value))
+            .transpose()
+    }
+
+    /// read the `$ patches` original values, the byte value before it was
+    /// patched, by address
+    pub fn patches_original_values(&self) -> Result<Vec<(u64, u64)>> {
+        self.patches_entries(b'A')?
+            .into_iter()
+            .map(|(address, value)| Ok((address, parse_node_value(value)?)))
+            .collect()
+    }
+
+    /// read the `$ patches` `P` entries, by address
+    // TODO the meaning is unknown, only the value 0x01 was observed
+    pub fn patches_flags(&self) -> Result<Vec<(u64, u8)>> {
+        self.patches_entries(b'P')?
+            .into_iter()
+            .map(|(address, value)| match value {
+                [flags] => Ok((address, *flags)),
+                _ => Err(anyhow!("Invalid patches flag value")),
+            })
+            .collect()
+    }
+
+    fn patches_entries(&self, tag: u8) -> Result<Vec<(u64, &[u8])>> {
+        let Some(node) = self.netnode_idx_by_name("$ patches")? else {
+            return Ok(vec![]);
+        };
+        let key = NetnodeKey::new(node, self.is_64)?.with_tag(tag);
+        let prefix_len = key.as_ref().len();
+        self.sub_values(key)
+            .map(|entry| {
+                let address =
+                    parse_number(&entry.key[prefix_len..], true, self.is_64)
+                        .ok_or_else(|| anyhow!("Invalid patches key"))?;
+                Ok((address, &entry.value[..]))
+            })
+            .collect()
+    }
+
     /// read the `$ segs` entries of the database
     pub fn segments(
         &self,
@@ -370,19 +556,29 @@ impl ID0Section {
             .map(|e| Ok(CStr::from_bytes_with_nul(&e.value)?.to_str()?)))
     }
 
+    /// read all the `$ loader name` entries of the database
+    pub fn loader_names(&self) -> Result<Vec<String>> {
+        self.loader_name()?
+            .map(|name| name.map(str::to_owned))
+            .collect()
+    }
+
+    /// read the original input file path from the `Root Node`, if any
+    pub fn input_file_path(&self) -> Result<Option<Cow<'_, str>>> {
+        let Some(entry) = self.get(self.root_node()?.with_tag(b'V')) else {
+            return Ok(None);
+        };
+        let path = parse_maybe_cstr(&entry.value)
+            .ok_or_else(|| anyhow!("Invalid input file path"))?;
+        Ok(Some(String::from_utf8_lossy(path)))
+    }
+
     /// read the `Root Node` entries of the database
     pub fn root_info(
         &self,
-    ) -> Result<impl Iterator<Item = Result<IDBRootInfo>>> {
-        let entry = self
-            .get("NRoot Node")
-            .ok_or_else(|| anyhow!("Unable to find entry Root Node"))?;
-        let key: Vec<u8> = b"."
-            .iter()
-            .chain(entry.value.iter().rev())
-            .copied()
-            .collect();
-        let key_len = key.len();
+    ) -> Result<impl Iterator<Item = Result<IDBRootInfo<'_>>>> {
+        let key = self.root_node()?;
+        let key_len = key.as_ref().len();
         Ok(self.sub_values(key).map(move |entry| {
             let sub_key = &entry.key[key_len..];
             let Some(sub_type) = sub_key.first().copied() else {
@@ -405,21 +601,27 @@ impl ID0Section {
             else {
                 return Ok(IDBRootInfo::Unknown(entry));
             };
-            match (sub_type, value as i64) {
-                (b'A', -6) => parse_number(&entry.value, false, self.is_64)
-                    .ok_or_else(|| anyhow!("Unable to parse imagebase value"))
+            // alt values are negative, so sign extend if 32bits
+            let value = if self.is_64 {
+                value as i64
+            } else {
+                (value as u32 as i32).into()
+            };
+            match (sub_type, value) {
+                (b'A', -6) => self
+                    .parse_usize_value(&entry.value)
                     .map(IDBRootInfo::ImageBase),
-                (b'A', -5) => parse_number(&entry.value, false, self.is_64)
-                    .ok_or_else(|| anyhow!("Unable to parse crc value"))
-                    .map(IDBRootInfo::Crc),
-                (b'A', -4) => parse_number(&entry.value, false, self.is_64)
-                    .ok_or_else(|| anyhow!("Unable to parse open_count value"))
+                (b'A', -5) => {
+                    self.parse_usize_value(&entry.value).map(IDBRootInfo::Crc)
+                }
+                (b'A', -4) => self
+                    .parse_usize_value(&entry.value)
                     .map(IDBRootInfo::OpenCount),
-                (b'A', -2) => parse_number(&entry.value, false, self.is_64)
-                    .ok_or_else(|| anyhow!("Unable to parse CreatedDate value"))
+                (b'A', -2) => self
+                    .parse_usize_value(&entry.value)
                     .map(IDBRootInfo::CreatedDate),
-                (b'A', -1) => parse_number(&entry.value, false, self.is_64)
-                    .ok_or_else(|| anyhow!("Unable to parse Version value"))
+                (b'A', -1) => self
+                    .parse_usize_value(&entry.value)
                     .map(IDBRootInfo::Version),
                 (b'S', 1302) => entry
                     .value
@@ -449,26 +651,236 @@ impl ID0Section {
 
     /// read the `Root Node` ida_info entry of the database
     pub fn ida_info(&self) -> Result<IDBParam> {
+        self.ida_info_with_report(&mut ParseReport::new())
+    }
+
+    /// same as [ID0Section::ida_info], flags with unknown bits set are
+    /// recorded in the report
+    pub fn ida_info_with_report(
+        &self,
+        report: &mut ParseReport,
+    ) -> Result<IDBParam> {
         // TODO Root Node is always the last one?
+        let key = self.root_node_key(b'S', 0x41B994)?;
+        let description = self.sub_values(key).next().ok_or_else(|| {
+            anyhow!("Unable to find id_params inside Root Node")
+        })?;
+        let param = IDBParam::read(&description.value, self.is_64)?;
+        if let IDBParam::V2(param2) = &param {
+            let bits = param2.genflags.unknown_bits();
+            report.ensure(bits == 0, || Warning::UnknownFlagBits {
+                field: "INFFL",
+                bits: bits.into(),
+            })?;
+            let bits = param2.af.unknown_bits2();
+            report.ensure(bits == 0, || Warning::UnknownFlagBits {
+                field: "AF2",
+                bits: bits.into(),
+            })?;
+        }
+        Ok(param)
+    }
+
+    /// read the `Root Node` values, see [RootInfo]
+    pub fn parsed_root_info(&self) -> Result<RootInfo> {
+        RootInfo::from_root_info(self.root_info()?)
+    }
+
+    /// read the image base from the `Root Node`, if any, see
+    /// [RootInfo::image_base]
+    ///
+    /// NOTE this is the `RIDX_ALT_IMAGEBASE` value, it's not related to
+    /// [IDBParam2::baseaddr], that is the program base in paragraphs.
+    pub fn image_base(&self) -> Result<Option<u64>> {
+        // RIDX_ALT_IMAGEBASE
+        self.root_node_alt(-6)
+    }
+
+    /// read the size of the input file from the `Root Node`, if any
+    pub fn input_file_size(&self) -> Result<Option<u64>> {
+        // RIDX_ALT_FSIZE
+        self.root_node_alt(-8)
+    }
+
+    /// read the database creation time from the `Root Node`, if any, in
+    /// seconds since the unix epoch (1970-01-01 00:00:00 UTC)
+    pub fn database_creation_time(&self) -> Result<Option<u64>> {
+        // RIDX_ALT_CTIME
+        self.root_node_alt(-2)
+    }
+
+    /// read the accumulated time the database was open from the
+    /// `Root Node`, if any, in seconds
+    pub fn database_secs_opens(&self) -> Result<Option<u64>> {
+        // RIDX_ALT_ELAPSED
+        self.root_node_alt(-3)
+    }
+
+    /// [ID0Section::database_creation_time] as a UTC timestamp
+    #[cfg(feature = "time")]
+    pub fn database_creation_time_utc(
+        &self,
+    ) -> Result<Option<time::OffsetDateTime>> {
+        self.database_creation_time()?
+            .map(|secs| {
+                let secs = i64::try_from(secs)?;
+                Ok(time::OffsetDateTime::from_unix_timestamp(secs)?)
+            })
+            .transpose()
+    }
+
+    /// [ID0Section::database_secs_opens] as a duration
+    #[cfg(feature = "time")]
+    pub fn database_open_duration(&self) -> Result<Option<time::Duration>> {
+        self.database_secs_opens()?
+            .map(|secs| Ok(time::Duration::seconds(i64::try_from(secs)?)))
+            .transpose()
+    }
+
+    /// the `Root Node` alt values are indexed by negative numbers
+    fn root_node_alt(&self, idx: i32) -> Result<Option<u64>> {
+        let idx = if self.is_64 {
+            i64::from(idx) as u64
+        } else {
+            (idx as u32).into()
+        };
+        self.get(self.root_node_key(b'A', idx)?)
+            .map(|entry| self.parse_usize_value(&entry.value))
+            .transpose()
+    }
+
+    /// the netnode key of the `Root Node`
+    fn root_node(&self) -> Result<NetnodeKey> {
         let entry = self
             .get("NRoot Node")
             .ok_or_else(|| anyhow!("Unable to find entry Root Node"))?;
-        let sub_key = if self.is_64 {
-            0x41B994u64.to_be_bytes().to_vec()
-        } else {
-            0x41B994u32.to_be_bytes().to_vec()
+        NetnodeKey::from_node_value(&entry.value, self.is_64)
+    }
+
+    fn root_node_key(&self, tag: u8, idx: u64) -> Result<NetnodeKey> {
+        self.root_node()?.with_tag(tag).with_index(idx)
+    }
+
+    /// the netnode value at `node`, `tag` and `idx`, decoded as a
+    /// little-endian integer with the size of the database addresses
+    pub fn sup_value_usize(
+        &self,
+        node: u64,
+        tag: u8,
+        idx: u64,
+    ) -> Result<Option<u64>> {
+        self.get(self.netnode_key(node, tag, idx)?)
+            .map(|entry| self.parse_usize_value(&entry.value))
+            .transpose()
+    }
+
+    /// the netnode value at `node`, `tag` and `idx`, decoded as a
+    /// little-endian `u32`
+    pub fn sup_value_u32(
+        &self,
+        node: u64,
+        tag: u8,
+        idx: u64,
+    ) -> Result<Option<u32>> {
+        self.get(self.netnode_key(node, tag, idx)?)
+            .map(|entry| {
+                <[u8; 4]>::try_from(&entry.value[..])
+                    .map(u32::from_le_bytes)
+                    .map_err(|_| {
+                        anyhow!(
+                            "Invalid netnode u32 value with len {}",
+                            entry.value.len()
+                        )
+                    })
+            })
+            .transpose()
+    }
+
+    fn netnode_key(&self, node: u64, tag: u8, idx: u64) -> Result<NetnodeKey> {
+        NetnodeKey::new(node, self.is_64)?
+            .with_tag(tag)
+            .with_index(idx)
+    }
+
+    fn parse_usize_value(&self, value: &[u8]) -> Result<u64> {
+        parse_number(value, false, self.is_64).ok_or_else(|| {
+            anyhow!("Invalid netnode usize value with len {}", value.len())
+        })
+    }
+
+    fn root_node_sup_cstr(&self, idx: u64) -> Result<Option<IDBString>> {
+        let Some(entry) = self.get(self.root_node_key(b'S', idx)?) else {
+            return Ok(None);
         };
-        let key: Vec<u8> = b"."
-            .iter()
-            .chain(entry.value.iter().rev())
-            .chain(b"S")
-            .chain(sub_key.iter())
-            .copied()
-            .collect();
-        let description = self.sub_values(key).next().ok_or_else(|| {
-            anyhow!("Unable to find id_params inside Root Node")
-        })?;
-        IDBParam::read(&description.value, self.is_64)
+        let value = parse_maybe_cstr(&entry.value)
+            .ok_or_else(|| anyhow!("Invalid Root Node CStr at {idx}"))?;
+        Ok(Some(IDBString::new(value.to_vec())))
+    }
+
+    /// read the ABI name from the `Root Node`, if any
+    pub fn abi_name(&self) -> Result<Option<IDBString>> {
+        // RIDX_ABINAME
+        self.root_node_sup_cstr(1350)
+    }
+
+    /// read the path of the archive the input file was extracted from, if any
+    pub fn archive_file_path(&self) -> Result<Option<IDBString>> {
+        // RIDX_ARCHIVE_PATH
+        self.root_node_sup_cstr(1351)
+    }
+
+    /// read the user notepad from the `Root Node`, if any
+    pub fn notepad(&self) -> Result<Option<IDBString>> {
+        // RIDX_NOTEPAD, stored as a blob, one entry for each chunk
+        const RIDX_NOTEPAD: u64 = 68;
+        let start = self.root_node_key(b'S', RIDX_NOTEPAD)?;
+        let start = start.as_ref();
+        let key_len = start.len() - if self.is_64 { 8 } else { 4 };
+        let key = &start[..key_len];
+        let Ok(first) = self.binary_search(start) else {
+            return Ok(None);
+        };
+        let mut data = vec![];
+        for (i, entry) in self.entries[first..].iter().enumerate() {
+            let Some(idx) = entry
+                .key
+                .strip_prefix(key)
+                .and_then(|idx| parse_number(idx, true, self.is_64))
+            else {
+                break;
+            };
+            // the blob ends on the first missing chunk
+            if idx != RIDX_NOTEPAD + i as u64 {
+                break;
+            }
+            data.extend_from_slice(&entry.value);
+        }
+        Ok(Some(IDBString::from_nul_terminated(&data)))
+    }
+
+    /// read the list of string encodings used by the database, if any
+    pub fn strings_encodings(&self) -> Result<Option<Vec<IDBString>>> {
+        // RIDX_STR_ENCODINGS
+        let Some(entry) = self.get(self.root_node_key(b'S', 1305)?) else {
+            return Ok(None);
+        };
+        let mut value = &entry.value[..];
+        // the number of encodings + 1, 0 is reserved for the default one
+        let encodings_len = value.unpack_dd()?.saturating_sub(1);
+        // default encoding for 1, 2 and 4 bytes strings
+        let defaults_len = value.unpack_dd()?;
+        for _ in 0..defaults_len {
+            let default = value.unpack_dd()?;
+            ensure!(
+                default <= encodings_len,
+                "Invalid default string encoding {default}"
+            );
+        }
+        let encodings = (0..encodings_len)
+            .map(|_| value.unpack_ds().map(IDBString::new))
+            .collect::<Result<_>>()?;
+        ensure!(value.is_empty(), "Unparsed data in strings encodings");
+        Ok(Some(encodings))
     }
 
     /// read the `$ fileregions` entries of the database
@@ -493,13 +905,26 @@ impl ID0Section {
         }))
     }
 
+    /// read the `$ fileregions` entries of the database, using the version
+    /// from the `Root Node`
+    pub fn file_regions_auto(
+        &self,
+    ) -> Result<impl Iterator<Item = Result<IDBFileRegions>> + '_> {
+        let version = self.ida_info()?.version();
+        self.file_regions(version)
+    }
+
     /// read the `$ funcs` entries of the database
+    ///
+    /// NOTE the layout of the entries depends on the version from the
+    /// `Root Node`
     pub fn functions_and_comments(
         &self,
-    ) -> Result<impl Iterator<Item = Result<FunctionsAndComments>>> {
+    ) -> Result<impl Iterator<Item = Result<FunctionsAndComments<'_>>>> {
         let entry = self
             .get("N$ funcs")
             .ok_or_else(|| anyhow!("Unable to find functions"))?;
+        let version = self.ida_info()?.version();
         let key: Vec<u8> = b"."
             .iter()
             .chain(entry.value.iter().rev())
@@ -508,11 +933,119 @@ impl ID0Section {
         let key_len = key.len();
         Ok(self.sub_values(key).map(move |e| {
             let key = &e.key[key_len..];
-            FunctionsAndComments::read(key, &e.value, self.is_64)
+            FunctionsAndComments::read(key, &e.value, version, self.is_64)
         }))
     }
 
-    // TODO implement $ fixups
+    /// read all the function chunks from `$ funcs`, both tail and non-tail
+    pub fn functions(
+        &self,
+    ) -> Result<impl Iterator<Item = Result<IDBFunction>> + '_> {
+        Ok(self
+            .functions_and_comments()?
+            .filter_map(|entry| match entry {
+                Ok(FunctionsAndComments::Function(function)) => {
+                    Some(Ok(function))
+                }
+                Ok(_) => None,
+                Err(err) => Some(Err(err)),
+            }))
+    }
+
+    /// read all the chunks of the function that starts at the address, the
+    /// entry chunk first, followed by all the tail chunks owned by it
+    pub fn function_chunks(
+        &self,
+        address: impl Id0AddressKey,
+    ) -> Result<Vec<IDBFunction>> {
+        let address = address.as_u64();
+        let mut entry = None;
+        let mut tails = vec![];
+        for function in self.functions()? {
+            let function = function?;
+            if !function.flags().is_tail() {
+                if function.address.start == address {
+                    entry = Some(function);
+                }
+            } else if let Some(IDBFunctionExtra::Tail { owner, .. }) =
+                &function.extra
+            {
+                if *owner == address {
+                    tails.push(function);
+                }
+            }
+        }
+        Ok(entry.into_iter().chain(tails).collect())
+    }
+
+    /// find the function that contains the address, if the address is part
+    /// of a tail chunk, the function that owns it is returned
+    pub fn function_containing(
+        &self,
+        address: impl Id0AddressKey,
+    ) -> Result<Option<IDBFunction>> {
+        let address = address.as_u64();
+        let Some(entry) = self.get("N$ funcs") else {
+            return Ok(None);
+        };
+        if !self.is_64 && u32::try_from(address).is_err() {
+            return Ok(None);
+        }
+        let version = self.ida_info()?.version();
+        let chunks = NetnodeKey::from_node_value(&entry.value, self.is_64)?
+            .with_tag(b'S');
+        // chunks are keyed by the start address and don't overlap, so only
+        // the last chunk starting at or before the address can contain it
+        let idx = match self.binary_search(chunks.with_index(address)?) {
+            Ok(idx) => idx,
+            Err(0) => return Ok(None),
+            Err(idx) => idx - 1,
+        };
+        let entry = &self.entries[idx];
+        let Some(sub_key) = entry.key.strip_prefix(chunks.as_ref()) else {
+            return Ok(None);
+        };
+        let chunk =
+            IDBFunction::read(sub_key, &entry.value, version, self.is_64)?;
+        if !chunk.address.contains(&address) {
+            return Ok(None);
+        }
+        let Some(IDBFunctionExtra::Tail { owner, .. }) = chunk.extra else {
+            return Ok(Some(chunk));
+        };
+        // BADADDR or an address that don't fit this database
+        if owner == u64::MAX || (!self.is_64 && u32::try_from(owner).is_err()) {
+            #[cfg(feature = "restrictive")]
+            return Err(anyhow!("Invalid function owner {owner:#x}"));
+            #[cfg(not(feature = "restrictive"))]
+            return Ok(None);
+        }
+        let Some(entry) = self.get(chunks.with_index(owner)?) else {
+            #[cfg(feature = "restrictive")]
+            return Err(anyhow!("Missing function owner {owner:#x}"));
+            #[cfg(not(feature = "restrictive"))]
+            return Ok(None);
+        };
+        let sub_key = &entry.key[chunks.as_ref().len()..];
+        IDBFunction::read(sub_key, &entry.value, version, self.is_64).map(Some)
+    }
+
+    /// read the `$ fixups` entries of the database, sorted by address
+    pub fn fixups(&self) -> Result<impl Iterator<Item = Result<Fixup>> + '_> {
+        let entry = self
+            .get("N$ fixups")
+            .ok_or_else(|| anyhow!("Unable to find fixups"))?;
+        let key: Vec<u8> = b"."
+            .iter()
+            .chain(entry.value.iter().rev())
+            .chain(b"S")
+            .copied()
+            .collect();
+        let key_len = key.len();
+        Ok(self
+            .sub_values(key)
+            .map(move |e| Fixup::read(&e.key[key_len..], &e.value, self.is_64)))
+    }
     // TODO implement $ imports
     // TODO implement $ scriptsnippets
     // TODO implement $ enums
@@ -523,7 +1056,7 @@ impl ID0Section {
 
     fn entry_points_raw(
         &self,
-    ) -> Result<impl Iterator<Item = Result<EntryPointRaw>>> {
+    ) -> Result<impl Iterator<Item = Result<EntryPointRaw<'_>>>> {
         let entry = self
             .get("N$ entry points")
             .ok_or_else(|| anyhow!("Unable to find functions"))?;
@@ -541,6 +1074,14 @@ impl ID0Section {
 
     /// read the `$ entry points` entries of the database
     pub fn entry_points(&self) -> Result<Vec<EntryPoint>> {
+        self.entry_points_with_cache(&mut til::TypeCache::new())
+    }
+
+    /// same as [ID0Section::entry_points], decoding types with `cache`
+    pub fn entry_points_with_cache(
+        &self,
+        cache: &mut til::TypeCache,
+    ) -> Result<Vec<EntryPoint>> {
         type RawEntryPoint<'a> =
             HashMap<u64, (Option<u64>, Option<&'a str>, Option<&'a str>)>;
         let mut entry_points: RawEntryPoint = HashMap::new();
@@ -588,11 +1129,12 @@ impl ID0Section {
                     | (None, _, Some(_))
                     | (None, _, None) => None,
                     (Some(address), forwarded, Some(name)) => {
-                        let entry =
-                            match self.find_entry_point_type(key, address) {
-                                Ok(entry) => entry,
-                                Err(error) => return Some(Err(error)),
-                            };
+                        let entry = match self
+                            .find_entry_point_type(cache, key, address)
+                        {
+                            Ok(entry) => entry,
+                            Err(error) => return Some(Err(error)),
+                        };
                         Some(Ok(EntryPoint {
                             name: name.to_owned(),
                             address,
@@ -609,17 +1151,18 @@ impl ID0Section {
 
     fn find_entry_point_type(
         &self,
+        cache: &mut til::TypeCache,
         key: u64,
         address: u64,
     ) -> Result<Option<til::Type>> {
         if let Some(key_entry) =
-            self.find_entry_point_type_value(key, 0x3000)?
+            self.find_entry_point_type_value(cache, key, 0x3000)?
         {
             return Ok(Some(key_entry));
         }
         // TODO some times it uses the address as key, it's based on the version?
         if let Some(key_entry) =
-            self.find_entry_point_type_value(address, 0x3000)?
+            self.find_entry_point_type_value(cache, address, 0x3000)?
         {
             return Ok(Some(key_entry));
         }
@@ -628,6 +1171,7 @@ impl ID0Section {
 
     fn find_entry_point_type_value(
         &self,
+        cache: &mut til::TypeCache,
         value: u64,
         key_find: u64,
     ) -> Result<Option<til::Type>> {
@@ -647,8 +1191,9 @@ impl ID0Section {
             let key = parse_number(key, true, self.is_64).unwrap();
             // TODO handle other values for the key
             if key == key_find {
-                return til::Type::new_from_id0(&entry.value, vec![])
-                    .map(Option::Some);
+                return cache
+                    .new_from_id0(&entry.value, vec![])
+                    .map(|ty| Some(ty.clone()));
             }
         }
         Ok(None)
@@ -658,7 +1203,7 @@ impl ID0Section {
     pub fn address_info(
         &self,
         version: u16,
-    ) -> Result<impl Iterator<Item = Result<(u64, AddressInfo)>>> {
+    ) -> Result<impl Iterator<Item = Result<(u64, AddressInfo<'_>)>>> {
         let regions = self.file_regions(version)?;
         Ok(SectionAddressInfoIter::new(
             &self.entries[..],
@@ -667,17 +1212,53 @@ impl ID0Section {
         ))
     }
 
+    /// read the address information only for addresses that are the start
+    /// of an instruction, according to the `ID1` byte flags
+    pub fn code_address_info<'a>(
+        &'a self,
+        version: u16,
+        id1: &'a ID1Section,
+    ) -> Result<impl Iterator<Item = Result<(u64, AddressInfo<'a>)>> + 'a> {
+        self.address_info_by_type(version, id1, ByteType::Code)
+    }
+
+    /// read the address information only for addresses that are the start
+    /// of a data item, according to the `ID1` byte flags
+    pub fn data_address_info<'a>(
+        &'a self,
+        version: u16,
+        id1: &'a ID1Section,
+    ) -> Result<impl Iterator<Item = Result<(u64, AddressInfo<'a>)>> + 'a> {
+        self.address_info_by_type(version, id1, ByteType::Data)
+    }
+
+    fn address_info_by_type<'a>(
+        &'a self,
+        version: u16,
+        id1: &'a ID1Section,
+        byte_type: ByteType,
+    ) -> Result<impl Iterator<Item = Result<(u64, AddressInfo<'a>)>> + 'a> {
+        let regions = self.file_regions(version)?;
+        Ok(
+            SectionAddressInfoIter::new(&self.entries[..], regions, self.is_64)
+                .with_filter(id1, byte_type),
+        )
+    }
+
     /// read the address information for the address
     pub fn address_info_at(
         &self,
         address: impl Id0AddressKey,
-    ) -> Result<impl Iterator<Item = Result<AddressInfo>>> {
-        let address = address.as_u64();
-        let key: Vec<u8> = key_from_address(address, self.is_64).collect();
-        let start = self.binary_search(&key).unwrap_or_else(|start| start);
-        let end = self.binary_search_end(&key).unwrap_or_else(|end| end);
-
-        let entries = &self.entries[start..end];
+    ) -> Result<impl Iterator<Item = Result<AddressInfo<'_>>>> {
+        let entries = match self.address_key(address.as_u64()) {
+            Some(key) => {
+                let start =
+                    self.binary_search(key).unwrap_or_else(|start| start);
+                let end = self.binary_search_end(key).unwrap_or_else(|end| end);
+                &self.entries[start..end]
+            }
+            None => &[][..],
+        };
         // ignore the address, it will always be the same, the one request
         let iter = AddressInfoIter::new(entries, self.is_64)
             .map(|value| value.map(|(_addr, value)| value));
@@ -689,15 +1270,16 @@ impl ID0Section {
         &self,
         id0_addr: impl Id0AddressKey,
     ) -> Result<Option<&[u8]>> {
-        let key: Vec<u8> = key_from_address(id0_addr.as_u64(), self.is_64)
-            .chain(Some(b'N'))
-            .collect();
-        let Ok(start) = self.binary_search(&key) else {
+        let Some(key) = self.address_key(id0_addr.as_u64()) else {
+            return Ok(None);
+        };
+        let key = key.with_tag(b'N');
+        let Ok(start) = self.binary_search(key) else {
             return Ok(None);
         };
 
         let entry = &self.entries[start];
-        let key_len = key.len();
+        let key_len = key.as_ref().len();
         let key = &entry.key[key_len..];
         ensure!(key.is_empty(), "Label ID0 entry with key");
         let label = parse_maybe_cstr(&entry.value)
@@ -705,6 +1287,258 @@ impl ID0Section {
         Ok(Some(label))
     }
 
+    /// all the named addresses of the `NAM` section with the label from the
+    /// `ID0`, addresses without a label are skipped
+    pub fn names<'a>(
+        &'a self,
+        nam: &'a NamSection,
+    ) -> impl Iterator<Item = Result<(u64, IDBString)>> + 'a {
+        let mut last = None;
+        nam.names.iter().filter_map(move |address| {
+            // the addresses are sorted, so duplicates are consecutive
+            if last.replace(*address) == Some(*address) {
+                return None;
+            }
+            // NOTE it's know that some label are missing in some databases
+            self.label_at(*address).transpose().map(|label| {
+                label.map(|label| (*address, IDBString::new(label.to_vec())))
+            })
+        })
+    }
+
+    /// read the local labels, AKA `SN_LOCAL` names, inside the function chunk
+    pub fn local_labels(
+        &self,
+        function: &IDBFunction,
+    ) -> Result<Vec<(u64, &[u8])>> {
+        // NALT_AFLAGS
+        const AFLAGS_IDX: u64 = 8;
+        const AFL_LNAME: u64 = 0x4000;
+        let start = key_from_address(function.address.start, self.is_64)?;
+        let end = key_from_address(function.address.end, self.is_64)?;
+        let start = self.binary_search(start).unwrap_or_else(|start| start);
+        let end = self.binary_search(end).unwrap_or_else(|end| end);
+        let addr_len = if self.is_64 { 8 } else { 4 };
+
+        let mut labels = vec![];
+        for entry in &self.entries[start..end] {
+            let [b'.', addr @ .., b'N'] = &entry.key[..] else {
+                continue;
+            };
+            let Some(address) = parse_number(addr, true, self.is_64) else {
+                continue;
+            };
+            debug_assert_eq!(addr.len(), addr_len);
+            let aflags_key = key_from_address(address, self.is_64)?
+                .with_tag(b'A')
+                .with_index(AFLAGS_IDX)?;
+            let Some(aflags) = self.get(aflags_key) else {
+                continue;
+            };
+            if parse_node_value(&aflags.value)? & AFL_LNAME == 0 {
+                continue;
+            }
+            let label = parse_maybe_cstr(&entry.value)
+                .ok_or_else(|| anyhow!("Label is not valid CStr"))?;
+            labels.push((address, label));
+        }
+        Ok(labels)
+    }
+
+    /// the netnode key for the address, `None` if the address can't exist in
+    /// this database, eg: bigger than `u32::MAX` in a 32 bits database
+    fn address_key(&self, address: u64) -> Option<NetnodeKey> {
+        key_from_address(address, self.is_64).ok()
+    }
+
+    fn address_sup_key(&self, address: u64, idx: u64) -> Option<NetnodeKey> {
+        self.address_key(address)
+            .and_then(|key| key.with_tag(b'S').with_index(idx).ok())
+    }
+
+    fn comment_from_idx(
+        &self,
+        address: u64,
+        idx: u64,
+    ) -> Result<Option<&[u8]>> {
+        let Some(entry) = self
+            .address_sup_key(address, idx)
+            .and_then(|key| self.get(key))
+        else {
+            return Ok(None);
+        };
+        parse_maybe_cstr(&entry.value)
+            .map(Some)
+            .ok_or_else(|| anyhow!("Comment is not valid CStr"))
+    }
+
+    fn comments_from_range(
+        &self,
+        address: u64,
+        range: Range<u64>,
+    ) -> impl Iterator<Item = Result<&[u8]>> {
+        let start = self.address_sup_key(address, range.start);
+        let end = self.address_sup_key(address, range.end - 1);
+        start
+            .zip(end)
+            .into_iter()
+            .flat_map(|(start, end)| self.get_inclusive_range(start, end))
+            .map(|entry| {
+                parse_maybe_cstr(&entry.value)
+                    .ok_or_else(|| anyhow!("Comment is not valid CStr"))
+            })
+    }
+
+    /// read the comment set at address, if any
+    pub fn comment(
+        &self,
+        address: impl Id0AddressKey,
+    ) -> Result<Option<&[u8]>> {
+        self.comment_from_idx(address.as_u64(), 0)
+    }
+
+    /// read the repeatable comment set at address, if any
+    pub fn comment_repeatable(
+        &self,
+        address: impl Id0AddressKey,
+    ) -> Result<Option<&[u8]>> {
+        self.comment_from_idx(address.as_u64(), 1)
+    }
+
+    /// read all the pre comment lines set at address
+    pub fn comments_pre(
+        &self,
+        address: impl Id0AddressKey,
+    ) -> impl Iterator<Item = Result<&[u8]>> {
+        self.comments_from_range(address.as_u64(), 1000..2000)
+    }
+
+    /// read all the post comment lines set at address
+    pub fn comments_post(
+        &self,
+        address: impl Id0AddressKey,
+    ) -> impl Iterator<Item = Result<&[u8]>> {
+        self.comments_from_range(address.as_u64(), 2000..3000)
+    }
+
+    /// read the type applied at address, if any. The same as
+    /// [AddressInfo::applied_type], references to other types are solved
+    /// using the TIL section
+    pub fn applied_tinfo(
+        &self,
+        address: impl Id0AddressKey,
+        til: &til::section::TILSection,
+    ) -> Result<Option<til::Type>> {
+        self.applied_tinfo_with_cache(address, til, &mut til::TypeCache::new())
+    }
+
+    /// same as [ID0Section::applied_tinfo], decoding types with `cache`
+    pub fn applied_tinfo_with_cache(
+        &self,
+        address: impl Id0AddressKey,
+        til: &til::section::TILSection,
+        cache: &mut til::TypeCache,
+    ) -> Result<Option<til::Type>> {
+        // NSUP_TYPEINFO, followed by the fields and the continuation
+        const TYPEINFO_IDX: u64 = 0x3000;
+        const FIELDS_IDX: u64 = 0x3001;
+        const CONTINUATION_END_IDX: u64 = 0x3999;
+        let Some(key) = self.address_key(address.as_u64()) else {
+            return Ok(None);
+        };
+        let sup_key = |idx| key.with_tag(b'S').with_index(idx);
+        let Some(entry) = self.get(sup_key(TYPEINFO_IDX)?) else {
+            return Ok(None);
+        };
+        let fields = self
+            .get(sup_key(FIELDS_IDX)?)
+            .map(|entry| {
+                let value = parse_maybe_cstr(&entry.value)
+                    .ok_or_else(|| anyhow!("Incomplete Fields for TIL Type"))?;
+                crate::ida_reader::split_strings_from_array(value)
+                    .ok_or_else(|| anyhow!("Invalid Fields for TIL Type"))
+            })
+            .transpose()?
+            .unwrap_or_default();
+        let continuation = self.get_inclusive_range(
+            sup_key(FIELDS_IDX + 1)?,
+            sup_key(CONTINUATION_END_IDX)?,
+        );
+        let data: Vec<u8> = entry
+            .value
+            .iter()
+            .chain(continuation.flat_map(|entry| &entry.value[..]))
+            .copied()
+            .collect();
+        let ty = cache.new_from_id0(&data, fields)?;
+
+        let til::TypeVariant::Typeref(typeref) = &ty.type_variant else {
+            return Ok(Some(ty.clone()));
+        };
+        if let til::TyperefValue::UnsolvedName(None) = typeref.typeref_value {
+            return Ok(Some(ty.clone()));
+        }
+        typeref
+            .resolve_idx(til)
+            .map(|idx| Some(til.types[idx].tinfo.clone()))
+            .ok_or_else(|| anyhow!("Unable to find the applied type"))
+    }
+
+    /// all the string literals identified in the database, with the type
+    /// and the raw bytes of each literal, including any prefix/terminator
+    ///
+    /// NOTE uninitialized bytes are returned as zeros
+    pub fn string_literals<'a>(
+        &'a self,
+        id1: &'a ID1Section,
+    ) -> Result<impl Iterator<Item = Result<(u64, StrType, Vec<u8>)>> + 'a>
+    {
+        // NALT_STRTYPE, stored as the value + 1
+        const STRTYPE_IDX: u64 = 0x10;
+        let default = self
+            .ida_info()?
+            .str_type()
+            .ok_or_else(|| anyhow!("Invalid default string literal type"))?;
+        let literals = id1.segments().flat_map(|seg| {
+            let mut literals: Vec<(u64, Vec<u8>)> = vec![];
+            for (address, info) in seg.bytes() {
+                match info.byte_type() {
+                    ByteType::Data if info.is_string_literal() => literals
+                        .push((address, vec![info.value().unwrap_or(0)])),
+                    // the literal continues, if this tail belongs to it
+                    ByteType::Tail => {
+                        if let Some((start, data)) = literals.last_mut() {
+                            if *start + data.len() as u64 == address {
+                                data.push(info.value().unwrap_or(0));
+                            }
+                        }
+                    }
+                    ByteType::Code | ByteType::Data | ByteType::Unknown => {}
+                }
+            }
+            literals
+        });
+        Ok(literals.map(move |(address, data)| {
+            let key = key_from_address(address, self.is_64)?
+                .with_tag(b'A')
+                .with_index(STRTYPE_IDX)?;
+            let value = self
+                .get(key)
+                .map(|entry| parse_node_value(&entry.value))
+                .transpose()?;
+            // zero, like a missing value, means the default type
+            let Some(value) = value.and_then(|value| value.checked_sub(1))
+            else {
+                return Ok((address, default, data));
+            };
+            let str_type = u32::try_from(value)
+                .ok()
+                .and_then(StrType::from_raw)
+                .ok_or_else(|| anyhow!("Invalid string literal type"))?;
+            Ok((address, str_type, data))
+        }))
+    }
+
     pub(crate) fn dirtree_from_name<T: FromDirTreeNumber>(
         &self,
         name: impl AsRef<[u8]>,
@@ -863,8 +1697,9 @@ impl ID0Page {
     ) -> Result<Self> {
         let mut input = page_buf;
         let (preceding, count) = header(&mut input)?;
-        let min_data_pos = entry_len
-            .checked_mul(count + 2)
+        let min_data_pos = count
+            .checked_add(2)
+            .and_then(|count| entry_len.checked_mul(count))
             .ok_or_else(|| anyhow!("Invalid number of entries"))?;
         ensure!(
             min_data_pos <= id0_header.page_size,
@@ -1028,14 +1863,91 @@ impl ID0Page {
 pub(crate) fn key_from_address(
     address: u64,
     is_64: bool,
-) -> impl Iterator<Item = u8> {
-    b".".iter().copied().chain(if is_64 {
-        address.to_be_bytes().to_vec()
-    } else {
-        u32::try_from(address).unwrap().to_be_bytes().to_vec()
-    })
+) -> Result<NetnodeKey> {
+    NetnodeKey::new(address, is_64)
+}
+
+/// parse the netnode index, as stored on the `N` entries, or a alt value
+fn parse_node_value(value: &[u8]) -> Result<u64> {
+    match *value {
+        [a, b, c, d] => Ok(u32::from_le_bytes([a, b, c, d]).into()),
+        [a, b, c, d, e, f, g, h] => {
+            Ok(u64::from_le_bytes([a, b, c, d, e, f, g, h]))
+        }
+        _ => Err(anyhow!("Invalid netnode value")),
+    }
+}
+
+/// `.` + node + tag + index
+const NETNODE_KEY_MAX_LEN: usize = 1 + 8 + 1 + 8;
+
+/// netnode key built on the stack, so lookups don't allocate
+#[derive(Clone, Copy)]
+pub(crate) struct NetnodeKey {
+    data: [u8; NETNODE_KEY_MAX_LEN],
+    len: usize,
+    is_64: bool,
+}
+
+impl NetnodeKey {
+    /// errors if the node don't fit the database address size
+    pub(crate) fn new(node: u64, is_64: bool) -> Result<Self> {
+        let mut key = Self {
+            data: [0; NETNODE_KEY_MAX_LEN],
+            len: 0,
+            is_64,
+        };
+        key.push(b".");
+        key.push_number(node)?;
+        Ok(key)
+    }
+
+    /// create the key from the node value, as stored on the `N` entries
+    pub(crate) fn from_node_value(value: &[u8], is_64: bool) -> Result<Self> {
+        Self::new(parse_node_value(value)?, is_64)
+    }
+
+    pub(crate) fn with_tag(mut self, tag: u8) -> Self {
+        self.push(&[tag]);
+        self
+    }
+
+    /// errors if the index don't fit the database address size
+    pub(crate) fn with_index(mut self, idx: u64) -> Result<Self> {
+        self.push_number(idx)?;
+        Ok(self)
+    }
+
+    fn push_number(&mut self, value: u64) -> Result<()> {
+        if self.is_64 {
+            self.push(&value.to_be_bytes());
+        } else {
+            let value = u32::try_from(value).map_err(|_| {
+                anyhow!("Invalid netnode key {value:#x} for 32 bits database")
+            })?;
+            self.push(&value.to_be_bytes());
+        }
+        Ok(())
+    }
+
+    fn push(&mut self, bytes: &[u8]) {
+        self.data[self.len..self.len + bytes.len()].copy_from_slice(bytes);
+        self.len += bytes.len();
+    }
+}
+
+impl AsRef<[u8]> for NetnodeKey {
+    fn as_ref(&self) -> &[u8] {
+        &self.data[..self.len]
+    }
 }
 
 pub trait Id0AddressKey {
     fn as_u64(&self) -> u64;
 }
+
+impl Id0AddressKey for u64 {
+    fn as_u64(&self) -> u64 {
+        *self
+    }
+}
@@ -16,6 +16,41 @@ impl<T> DirTreeRoot<T> {
         Self::inner_visit_leafs(&mut handle, &self.entries);
     }
 
+    /// all the leafs, with the path of the folder that contains it, the root
+    /// folder is the empty path and sub-folders are separated by `/`, eg:
+    /// `folder/sub`
+    ///
+    /// NOTE the name of the leaf is not part of the dirtree, it need to be
+    /// taken from the value, eg: the name of the function at the address.
+    pub fn iter_paths(&self) -> impl Iterator<Item = (String, &T)> {
+        let mut output = vec![];
+        Self::inner_iter_paths(&mut output, "", &self.entries);
+        output.into_iter()
+    }
+
+    fn inner_iter_paths<'a>(
+        output: &mut Vec<(String, &'a T)>,
+        path: &str,
+        entries: &'a [DirTreeEntry<T>],
+    ) {
+        for entry in entries {
+            match entry {
+                DirTreeEntry::Leaf(entry) => {
+                    output.push((path.to_owned(), entry))
+                }
+                DirTreeEntry::Directory { name, entries } => {
+                    let name = String::from_utf8_lossy(name);
+                    let path = if path.is_empty() {
+                        name.into_owned()
+                    } else {
+                        format!("{path}/{name}")
+                    };
+                    Self::inner_iter_paths(output, &path, entries)
+                }
+            }
+        }
+    }
+
     fn inner_visit_leafs(
         handle: &mut impl FnMut(&T),
         entries: &[DirTreeEntry<T>],
@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::io::Read;
 
 use anyhow::Result;
@@ -23,6 +24,57 @@ pub enum IDBRootInfo<'a> {
     Unknown(&'a ID0Entry),
 }
 
+/// the commonly used values of the `Root Node`, parsed at once, see
+/// [ID0Section::parsed_root_info]
+#[derive(Clone, Debug)]
+pub struct RootInfo {
+    ida_info: IDBParam,
+    image_base: Option<u64>,
+}
+
+impl RootInfo {
+    pub(crate) fn from_root_info<'a>(
+        root_info: impl Iterator<Item = Result<IDBRootInfo<'a>>>,
+    ) -> Result<Self> {
+        let mut ida_info = None;
+        let mut image_base = None;
+        for info in root_info {
+            match info? {
+                IDBRootInfo::IDAInfo(info) => ida_info = Some(*info),
+                IDBRootInfo::ImageBase(value) => image_base = Some(value),
+                _ => {}
+            }
+        }
+        let ida_info = ida_info.ok_or_else(|| {
+            anyhow!("Unable to find id_params inside Root Node")
+        })?;
+        Ok(Self {
+            ida_info,
+            image_base,
+        })
+    }
+
+    /// the `ida_info` of the database
+    pub fn ida_info(&self) -> &IDBParam {
+        &self.ida_info
+    }
+
+    /// the image base, AKA `RIDX_ALT_IMAGEBASE`, that is the address the
+    /// input file was loaded at, if any
+    ///
+    /// NOTE this is not related to the loading base of the program in
+    /// [IDBParam2::baseaddr], that is in paragraphs and only meaningful for
+    /// segmented programs, usually zero otherwise
+    pub fn image_base(&self) -> Option<u64> {
+        self.image_base
+    }
+
+    /// same as [IDBParam::netdelta]
+    pub fn netdelta(&self) -> Option<u64> {
+        self.ida_info.netdelta()
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum IDBParam {
     V1(IDBParam1),
@@ -113,6 +165,8 @@ pub struct IDBParam1 {
     pub abiname: [u8; 16],
     pub abibits: u32,
     pub refcmts: u8,
+    /// extra data at the end, with unknown purpose
+    pub trailing_raw: Vec<u8>,
 }
 
 #[derive(Clone, Debug)]
@@ -172,6 +226,8 @@ pub struct IDBParam2 {
     pub strlit_sernum: u64,
     pub datatypes: u64,
     pub cc_id: Compiler,
+    /// the compiler id is only a guess, AKA `COMP_UNSURE`
+    pub cc_unsure: bool,
     pub cc_cm: u8,
     pub cc_size_i: u8,
     pub cc_size_b: u8,
@@ -186,7 +242,82 @@ pub struct IDBParam2 {
 }
 
 impl IDBParam {
-    pub(crate) fn read(data: &[u8], is_64: bool) -> Result<Self> {
+    /// the version of the database that produced this info
+    pub fn version(&self) -> u16 {
+        match self {
+            IDBParam::V1(IDBParam1 { version, .. })
+            | IDBParam::V2(IDBParam2 { version, .. }) => *version,
+        }
+    }
+
+    /// raw name of the processor module, eg: `metapc`
+    pub fn cpu(&self) -> &[u8] {
+        match self {
+            IDBParam::V1(IDBParam1 { cpu, .. })
+            | IDBParam::V2(IDBParam2 { cpu, .. }) => cpu,
+        }
+    }
+
+    /// processor module name, lossy converted to UTF-8
+    pub fn processor_name(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(self.cpu())
+    }
+
+    /// the processor module, identified by its name
+    pub fn processor(&self) -> Processor {
+        Processor::from_name(self.cpu())
+    }
+
+    /// the default string literal type
+    pub fn str_type(&self) -> Option<StrType> {
+        match self {
+            IDBParam::V1(IDBParam1 { strtype, .. }) => {
+                u32::try_from(*strtype).ok().and_then(StrType::from_raw)
+            }
+            IDBParam::V2(IDBParam2 { strtype, .. }) => {
+                StrType::from_raw(*strtype)
+            }
+        }
+    }
+
+    /// delta added to the netnode index of an address, only available
+    /// after version 7.0
+    pub fn netdelta(&self) -> Option<u64> {
+        match self {
+            IDBParam::V1(_) => None,
+            IDBParam::V2(IDBParam2 { netdelta, .. }) => Some(*netdelta),
+        }
+    }
+
+    /// parse the raw value of the `Root Node` sup entry `0x41b994`, eg:
+    /// extracted with [ID0Section::get]
+    ///
+    /// The value is parsed from memory, so any `&[u8]` works, including the
+    /// buffer of a [std::io::Cursor].
+    ///
+    /// ```
+    /// # fn main() -> anyhow::Result<()> {
+    /// use std::io::Cursor;
+    /// use idb_rs::id0::IDBParam;
+    ///
+    /// let file = std::fs::File::open("resources/idbs/gcc.i64")?;
+    /// let mut parser = idb_rs::IDBParser::new(std::io::BufReader::new(file))?;
+    /// let id0 = parser.read_id0_section(parser.id0_section_offset().unwrap())?;
+    /// // the key is `.` + node + tag + index, big-endian, in a 64 bits database
+    /// let root = id0.netnode_idx_by_name("Root Node")?.unwrap();
+    /// let key =
+    ///     [&b"."[..], &root.to_be_bytes(), b"S", &0x41b994u64.to_be_bytes()]
+    ///         .concat();
+    /// let data: &[u8] = &id0.get(&key).unwrap().value;
+    ///
+    /// let param = IDBParam::read(data, true)?;
+    /// let cursor = Cursor::new(data.to_vec());
+    /// let param_cursor = IDBParam::read(cursor.get_ref(), true)?;
+    /// assert_eq!(param.version(), param_cursor.version());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn read(data: &[u8], is_64: bool) -> Result<Self> {
         let mut input = IdaUnpacker::new(data, is_64);
         let magic: [u8; 3] = bincode::deserialize_from(&mut input)?;
         let magic_old = match &magic[..] {
@@ -216,17 +347,18 @@ impl IDBParam {
         cpu.truncate(cpu_str_part.len());
 
         // TODO tight those ranges up
-        let param = match version {
+        let mut param = match version {
             ..=699 => Self::read_v1(&mut input, version, cpu)?,
             700.. => Self::read_v2(&mut input, magic_old, version, cpu)?,
         };
-        match version {
+        let trailing = input.inner();
+        match &mut param {
             // TODO old version may contain extra data at the end with unknown purpose
-            ..=699 => {}
-            700.. => ensure!(
-                input.inner().is_empty(),
-                "Data left after the IDBParam",
-            ),
+            IDBParam::V1(param) => param.trailing_raw = trailing.to_vec(),
+            IDBParam::V2(_) => {
+                #[cfg(feature = "restrictive")]
+                ensure!(trailing.is_empty(), "Data left after the IDBParam");
+            }
         }
         Ok(param)
     }
@@ -402,6 +534,7 @@ impl IDBParam {
             abiname,
             abibits,
             refcmts,
+            trailing_raw: Vec::new(),
         }))
     }
 
@@ -481,7 +614,7 @@ impl IDBParam {
 
         let strlit_sernum = input.unpack_usize()?;
         let datatypes = input.unpack_usize()?;
-        let cc_id = Compiler::from_value(input.read_u8()?);
+        let (cc_id, cc_unsure) = Compiler::from_raw(input.read_u8()?);
         let cc_cm = input.read_u8()?;
         let cc_size_i = input.read_u8()?;
         let cc_size_b = input.read_u8()?;
@@ -550,6 +683,7 @@ impl IDBParam {
             strlit_sernum,
             datatypes,
             cc_id,
+            cc_unsure,
             cc_cm,
             cc_size_i,
             cc_size_b,
@@ -565,14 +699,60 @@ impl IDBParam {
     }
 }
 
+/// the most common processor modules, from the `cpu` name
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Processor {
+    /// x86 and x86_64, AKA `metapc`
+    X86,
+    Arm,
+    Mips,
+    Ppc,
+    Sparc,
+    M68k,
+    RiscV,
+    /// any other module, with the raw `cpu` name
+    Other(Vec<u8>),
+}
+
+impl Processor {
+    pub fn from_name(name: &[u8]) -> Self {
+        match &name.to_ascii_lowercase()[..] {
+            b"metapc" | b"8086" | b"80286r" | b"80286p" | b"80386r"
+            | b"80386p" | b"80486r" | b"80486p" | b"80586r" | b"80586p"
+            | b"80686p" | b"k62" | b"p2" | b"p3" | b"athlon" | b"p4" => {
+                Self::X86
+            }
+            b"arm" | b"armb" | b"arml" => Self::Arm,
+            b"mipsb" | b"mipsl" | b"mipsr" | b"mipsrl" | b"r5900b"
+            | b"r5900l" => Self::Mips,
+            b"ppc" | b"ppcl" => Self::Ppc,
+            b"sparcb" | b"sparcl" => Self::Sparc,
+            b"68k" | b"68000" | b"68010" | b"68020" | b"68030" | b"68040"
+            | b"68330" | b"68882" | b"68851" | b"68020ex" | b"colfire"
+            | b"68k-ext" => Self::M68k,
+            b"riscv" => Self::RiscV,
+            _ => Self::Other(name.to_vec()),
+        }
+    }
+}
+
 /// General idainfo flags
 #[derive(Debug, Clone, Copy)]
-pub struct Inffl(u8);
+pub struct Inffl(u16);
 impl Inffl {
     fn new(value: u16) -> Result<Self> {
-        ensure!(value < 0x100, "Invalid INFFL flag");
-        // TODO check for unused flags?
-        Ok(Self(value as u8))
+        #[cfg(feature = "restrictive")]
+        ensure!(value < 0x100, "Invalid INFFL flag {value:#x}");
+        // NOTE newer versions may set bits we don't know about, keep them
+        // so they are available with `unknown_bits` and reported by
+        // `ID0Section::ida_info_with_report`
+        Ok(Self(value))
+    }
+
+    /// the bits set that have no known meaning, newer versions of IDA may
+    /// set flags unknown to this crate
+    pub fn unknown_bits(&self) -> u16 {
+        self.0 & !0xFF
     }
 
     /// Autoanalysis is enabled?
@@ -612,8 +792,8 @@ impl Inffl {
 #[derive(Debug, Clone, Copy)]
 pub struct Lflg(u16);
 impl Lflg {
-    fn new(value: u32) -> Result<Self> {
-        ensure!(value < 0x1000, "Invalid LFLG flag");
+    pub(crate) fn new(value: u32) -> Result<Self> {
+        ensure!(value < 0x4000, "Invalid LFLG flag {value:#x}");
         Ok(Self(value as u16))
     }
 
@@ -665,14 +845,33 @@ impl Lflg {
     pub fn is_kernel_mode(&self) -> bool {
         self.0 & 0x0800 != 0
     }
+    /// 64-bit instructions with 64-bit registers, but 32-bit pointers and
+    /// address space
+    pub fn is_ilp32(&self) -> bool {
+        self.0 & 0x1000 != 0
+    }
+    /// unknown meaning, found in `kernel32.i64`
+    pub fn is_unknown_0x2000(&self) -> bool {
+        self.0 & 0x2000 != 0
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
-pub struct Af(u32, u8);
+pub struct Af(u32, u32);
 impl Af {
     fn new(value1: u32, value2: u32) -> Result<Self> {
+        #[cfg(feature = "restrictive")]
         ensure!(value2 < 0x10, "Invalid AF2 value {value2:#x}");
-        Ok(Self(value1, value2 as u8))
+        // NOTE newer versions may set bits we don't know about, keep them
+        // so they are available with `unknown_bits2` and reported by
+        // `ID0Section::ida_info_with_report`
+        Ok(Self(value1, value2))
+    }
+
+    /// the `AF2` bits set that have no known meaning, newer versions of IDA
+    /// may set flags unknown to this crate
+    pub fn unknown_bits2(&self) -> u32 {
+        self.1 & !0xF
     }
 
     /// Trace execution flow
@@ -1115,6 +1314,119 @@ impl StrLiteralFlags {
     }
 }
 
+/// width of each character in a string literal
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StrWidth {
+    /// 1 byte, eg: C strings and UTF-8
+    Byte1,
+    /// 2 bytes, eg: UTF-16
+    Byte2,
+    /// 4 bytes, eg: UTF-32
+    Byte4,
+}
+
+impl StrWidth {
+    pub fn bytes(self) -> usize {
+        match self {
+            StrWidth::Byte1 => 1,
+            StrWidth::Byte2 => 2,
+            StrWidth::Byte4 => 4,
+        }
+    }
+}
+
+/// how the length of a string literal is defined
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StrLayout {
+    /// ends with the termination characters, eg: C strings
+    Terminated,
+    /// prefixed with a 1 byte length, AKA pascal string
+    Prefix1,
+    /// prefixed with a 2 bytes length
+    Prefix2,
+    /// prefixed with a 4 bytes length
+    Prefix4,
+}
+
+impl StrLayout {
+    /// size of the length prefix in bytes, 0 for terminated strings
+    pub fn prefix_len(self) -> usize {
+        match self {
+            StrLayout::Terminated => 0,
+            StrLayout::Prefix1 => 1,
+            StrLayout::Prefix2 => 2,
+            StrLayout::Prefix4 => 4,
+        }
+    }
+}
+
+/// type of a string literal, AKA `strtype_t`, eg: [IDBParam2::strtype]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct StrType {
+    pub width: StrWidth,
+    pub layout: StrLayout,
+    /// character that ends the string, only used by [StrLayout::Terminated]
+    pub term1: u8,
+    /// alternative character that ends the string, 0 if none
+    pub term2: u8,
+    /// index of the encoding in the database list, 0 is the default one
+    /// for the width
+    pub encoding_idx: u8,
+}
+
+impl StrType {
+    /// C string, the default type
+    pub const C: StrType = StrType {
+        width: StrWidth::Byte1,
+        layout: StrLayout::Terminated,
+        term1: 0,
+        term2: 0,
+        encoding_idx: 0,
+    };
+
+    pub fn from_raw(value: u32) -> Option<Self> {
+        let width = match value & 0x3 {
+            0 => StrWidth::Byte1,
+            1 => StrWidth::Byte2,
+            2 => StrWidth::Byte4,
+            _ => return None,
+        };
+        let layout = match (value & 0xFC) >> 2 {
+            0 => StrLayout::Terminated,
+            1 => StrLayout::Prefix1,
+            2 => StrLayout::Prefix2,
+            3 => StrLayout::Prefix4,
+            _ => return None,
+        };
+        Some(Self {
+            width,
+            layout,
+            term1: (value >> 8) as u8,
+            term2: (value >> 16) as u8,
+            encoding_idx: (value >> 24) as u8,
+        })
+    }
+
+    pub fn into_raw(self) -> u32 {
+        let width = match self.width {
+            StrWidth::Byte1 => 0,
+            StrWidth::Byte2 => 1,
+            StrWidth::Byte4 => 2,
+        };
+        let layout = match self.layout {
+            StrLayout::Terminated => 0,
+            StrLayout::Prefix1 => 1,
+            StrLayout::Prefix2 => 2,
+            StrLayout::Prefix4 => 3,
+        };
+        width
+            | layout << 2
+            | u32::from(self.term1) << 8
+            | u32::from(self.term2) << 16
+            | u32::from(self.encoding_idx) << 24
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct AbiOptions(u16);
 impl AbiOptions {
@@ -1232,7 +1544,7 @@ impl FileType {
 }
 
 // InnerRef fb47a09e-b8d8-42f7-aa80-2435c4d1e049 0x7e6cc0
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Compiler {
     Unknown,
     VisualStudio,
@@ -1243,20 +1555,59 @@ pub enum Compiler {
     Delphi,
 
     // IDA LIB pring compiler_name allow any value here, printing it as "?"
-    Other,
+    Other(u8),
 }
 
 impl Compiler {
+    /// decode the compiler id, ignoring the `COMP_UNSURE` flag
     pub fn from_value(value: u8) -> Self {
-        match value {
-            0x0 => Self::Unknown,
-            0x1 => Self::VisualStudio,
-            0x2 => Self::Borland,
-            0x3 => Self::Watcom,
-            0x6 => Self::Gnu,
-            0x7 => Self::VisualAge,
-            0x8 => Self::Delphi,
-            _ => Self::Other,
+        Self::from_raw(value).0
+    }
+
+    /// decode the compiler id and the `COMP_UNSURE` flag, that is set if
+    /// the compiler id is only a guess
+    pub fn from_raw(value: u8) -> (Self, bool) {
+        use crate::til::flag::comp::*;
+        let compiler = match value & COMP_MASK {
+            COMP_UNK => Self::Unknown,
+            COMP_MS => Self::VisualStudio,
+            COMP_BC => Self::Borland,
+            COMP_WATCOM => Self::Watcom,
+            COMP_GNU => Self::Gnu,
+            COMP_VISAGE => Self::VisualAge,
+            COMP_BP => Self::Delphi,
+            other => Self::Other(other),
+        };
+        (compiler, value & COMP_UNSURE != 0)
+    }
+
+    /// the raw `COMP_*` value, without the `COMP_UNSURE` flag, see
+    /// [crate::til::flag::comp]
+    pub fn into_raw(self) -> u8 {
+        use crate::til::flag::comp::*;
+        match self {
+            Self::Unknown => COMP_UNK,
+            Self::VisualStudio => COMP_MS,
+            Self::Borland => COMP_BC,
+            Self::Watcom => COMP_WATCOM,
+            Self::Gnu => COMP_GNU,
+            Self::VisualAge => COMP_VISAGE,
+            Self::Delphi => COMP_BP,
+            Self::Other(value) => value,
+        }
+    }
+
+    /// the name of the compiler, as printed by IDA
+    pub fn name(self) -> &'static str {
+        match self {
+            Compiler::Unknown => "Unknown",
+            Compiler::VisualStudio => "Visual C++",
+            Compiler::Borland => "Borland C++",
+            Compiler::Watcom => "Watcom C++",
+            Compiler::Gnu => "GNU C++",
+            Compiler::VisualAge => "Visual Age C++",
+            Compiler::Delphi => "Delphi",
+            Compiler::Other(_) => "?",
         }
     }
 }
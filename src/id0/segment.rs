@@ -1,5 +1,6 @@
 use anyhow::Result;
 
+use crate::IDBString;
 use std::collections::HashMap;
 use std::num::{NonZeroU32, NonZeroU8};
 use std::ops::Range;
@@ -10,8 +11,8 @@ use super::*;
 pub struct Segment {
     pub address: Range<u64>,
     pub name: Option<Vec<u8>>,
-    // TODO class String
-    _class_id: u64,
+    /// the segment class, eg: `CODE`
+    pub class: Option<Vec<u8>>,
     /// This field is IDP dependent.
     /// You may keep your information about the segment here
     pub orgbase: u64,
@@ -42,6 +43,25 @@ pub struct Segment {
 }
 
 impl Segment {
+    /// the range of addresses of the segment
+    pub fn address_range(&self) -> Range<u64> {
+        self.address.clone()
+    }
+
+    /// `None` if the segment has no permission information, same for
+    /// [Segment::can_write] and [Segment::can_execute]
+    pub fn can_read(&self) -> Option<bool> {
+        self.perm.map(|perm| perm.can_read())
+    }
+
+    pub fn can_write(&self) -> Option<bool> {
+        self.perm.map(|perm| perm.can_write())
+    }
+
+    pub fn can_execute(&self) -> Option<bool> {
+        self.perm.map(|perm| perm.can_execute())
+    }
+
     pub(crate) fn read(
         value: &[u8],
         is_64: bool,
@@ -53,25 +73,11 @@ impl Segment {
         let startea = cursor.unpack_usize()?;
         let size = cursor.unpack_usize()?;
         let name_id = cursor.unpack_usize()?;
-        let name_id = NonZeroU32::new(u32::try_from(name_id).unwrap());
-        // TODO: I'm assuming name_id == 0 means no name, but maybe I'm wrong
-        let name = name_id
-            .map(|name_id| {
-                // TODO I think this is dependent on the version, and not on availability
-                if let Some(names) = names {
-                    names.get(&name_id).map(Vec::to_owned).ok_or_else(|| {
-                        anyhow!("Not found name for segment {name_id}")
-                    })
-                } else {
-                    // if there is no names, AKA `$ segstrings`, search for the key directly
-                    id0.name_by_index(name_id.get().into()).map(<[u8]>::to_vec)
-                }
-            })
-            .transpose();
-        let name = name?;
-        // TODO AKA [sclass](https://hex-rays.com//products/ida/support/sdkdoc/classsegment__t.html)
-        // I don't know what is this value or what it represents
-        let _class_id = cursor.unpack_usize()?;
+        let name = Self::read_name(name_id, names, id0)?;
+        // AKA [sclass](https://hex-rays.com//products/ida/support/sdkdoc/classsegment__t.html)
+        // stored the same way as the name
+        let class_id = cursor.unpack_usize()?;
+        let class = Self::read_name(class_id, names, id0)?;
         let orgbase = cursor.unpack_usize()?;
         let flags = SegmentFlag::from_raw(cursor.unpack_dd()?)
             .ok_or_else(|| anyhow!("Invalid Segment Flag value"))?;
@@ -98,7 +104,7 @@ impl Segment {
         Ok(Segment {
             address: startea..startea + size,
             name,
-            _class_id,
+            class,
             orgbase,
             flags,
             align,
@@ -111,9 +117,55 @@ impl Segment {
             color,
         })
     }
+
+    fn read_name(
+        name_id: u64,
+        names: Option<&HashMap<NonZeroU32, Vec<u8>>>,
+        id0: &ID0Section,
+    ) -> Result<Option<Vec<u8>>> {
+        let name_id = NonZeroU32::new(u32::try_from(name_id).unwrap());
+        // TODO: I'm assuming name_id == 0 means no name, but maybe I'm wrong
+        name_id
+            .map(|name_id| {
+                // TODO I think this is dependent on the version, and not on availability
+                if let Some(names) = names {
+                    names.get(&name_id).map(Vec::to_owned).ok_or_else(|| {
+                        anyhow!("Not found name for segment {name_id}")
+                    })
+                } else {
+                    // if there is no names, AKA `$ segstrings`, search for the key directly
+                    id0.name_by_index(name_id.get().into()).map(<[u8]>::to_vec)
+                }
+            })
+            .transpose()
+    }
+
+    /// the segment class, or the default class for the segment type
+    pub fn class_name(&self) -> IDBString {
+        if let Some(class) = &self.class {
+            return IDBString::new(class.clone());
+        }
+        let class = match self.seg_type {
+            SegmentType::Code => "CODE",
+            SegmentType::Data => "DATA",
+            SegmentType::Bss => "BSS",
+            SegmentType::Xtrn => "XTRN",
+            _ => "NORM",
+        };
+        IDBString::new(class.as_bytes().to_vec())
+    }
+
+    /// the segment name, if the segment is unnamed the class name is used
+    /// instead
+    pub fn display_name(&self) -> IDBString {
+        match &self.name {
+            Some(name) => IDBString::new(name.clone()),
+            None => self.class_name(),
+        }
+    }
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct SegmentFlag(u8);
 impl SegmentFlag {
     fn from_raw(value: u32) -> Option<Self> {
@@ -174,7 +226,7 @@ impl core::fmt::Debug for SegmentFlag {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum SegmentAlignment {
     /// Absolute segment.
     Abs,
@@ -233,7 +285,7 @@ impl SegmentAlignment {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum SegmentCombination {
     /// Private.
     ///
@@ -272,7 +324,7 @@ impl SegmentCombination {
     }
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct SegmentPermission(NonZeroU8);
 
 impl SegmentPermission {
@@ -283,6 +335,10 @@ impl SegmentPermission {
         Some(NonZeroU8::new(value as u8).map(Self))
     }
 
+    pub fn as_raw(&self) -> u8 {
+        self.0.get()
+    }
+
     pub fn can_execute(&self) -> bool {
         self.0.get() & 1 != 0
     }
@@ -312,7 +368,7 @@ impl core::fmt::Debug for SegmentPermission {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum SegmentBitness {
     S16Bits,
     S32Bits,
@@ -320,6 +376,14 @@ pub enum SegmentBitness {
 }
 
 impl SegmentBitness {
+    pub fn bits(self) -> u8 {
+        match self {
+            Self::S16Bits => 16,
+            Self::S32Bits => 32,
+            Self::S64Bits => 64,
+        }
+    }
+
     fn from_raw(value: u32) -> Option<Self> {
         match value {
             0 => Some(Self::S16Bits),
@@ -330,7 +394,7 @@ impl SegmentBitness {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum SegmentType {
     /// unknown type, no assumptions
     Norm,
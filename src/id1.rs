@@ -2,6 +2,7 @@ use anyhow::{anyhow, ensure, Result};
 
 use std::ops::Range;
 
+use crate::id0::{Segment, SegmentBitness};
 use crate::ida_reader::IdaGenericUnpack;
 use crate::{IDBHeader, IDBSectionCompression, VaVersion};
 
@@ -14,11 +15,220 @@ pub struct ID1Section {
 pub struct SegInfo {
     pub offset: u64,
     pub data: Vec<u8>,
-    // TODO find a way to decode this data
-    _flags: Vec<u32>,
+    // TODO find a way to decode the rest of this data
+    flags: Vec<u32>,
+}
+
+/// the class of a byte, from the `MS_CLS` bits of the byte flags
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ByteType {
+    /// first byte of an instruction
+    Code,
+    /// first byte of a data item
+    Data,
+    /// byte that is part of an instruction or data item, but not the first
+    Tail,
+    Unknown,
+}
+
+/// the bytes of a segment from the `ID0`, see [ID1Section::segments_with_id0]
+#[derive(Clone, Copy, Debug)]
+pub struct Id1Segment<'a> {
+    pub segment: &'a Segment,
+    id1: &'a ID1Section,
+}
+
+/// the value and flags of a single byte
+#[derive(Clone, Copy, Debug)]
+pub struct ByteInfo {
+    value: u8,
+    // NOTE stored without the byte value, AKA `>> 8`
+    flags: u32,
+}
+
+impl ByteInfo {
+    pub(crate) fn from_raw(raw: u32) -> Self {
+        Self {
+            value: raw as u8,
+            flags: raw >> 8,
+        }
+    }
+
+    /// the value of this byte, if initialized
+    pub fn value(&self) -> Option<u8> {
+        // FF_IVL
+        (self.flags & 0x1 != 0).then_some(self.value)
+    }
+
+    /// the class of the byte, eg: first byte of an instruction
+    pub fn byte_type(&self) -> ByteType {
+        ByteType::from_flags(self.flags)
+    }
+
+    /// true if this byte is the start of a string literal
+    pub fn is_string_literal(&self) -> bool {
+        // DT_TYPE == FF_STRLIT
+        self.byte_type() == ByteType::Data
+            && self.as_raw() & 0xF000_0000 == 0x5000_0000
+    }
+
+    /// the raw flags of this byte, as stored by IDA
+    pub fn as_raw(&self) -> u32 {
+        (self.flags << 8) | u32::from(self.value)
+    }
+}
+
+impl SegInfo {
+    /// the range of addresses in this segment
+    pub fn address_range(&self) -> Range<u64> {
+        self.offset..self.offset + self.data.len() as u64
+    }
+
+    /// the info of all bytes in this segment
+    pub fn bytes(&self) -> impl Iterator<Item = (u64, ByteInfo)> + '_ {
+        self.data.iter().zip(self.flags.iter()).enumerate().map(
+            |(i, (value, flags))| {
+                let info = ByteInfo {
+                    value: *value,
+                    flags: *flags,
+                };
+                (self.offset + i as u64, info)
+            },
+        )
+    }
+}
+
+impl<'a> Id1Segment<'a> {
+    /// the range of addresses of the segment
+    pub fn address_range(&self) -> Range<u64> {
+        self.segment.address_range()
+    }
+
+    /// number of bits in the segment addressing
+    pub fn bitness(&self) -> SegmentBitness {
+        self.segment.bitness
+    }
+
+    /// the selector of the segment, the segment base is the paragraph of
+    /// this selector, AKA `sel2para`
+    ///
+    /// NOTE the selectors table is not parsed, so the base itself is not
+    /// available
+    pub fn base_selector(&self) -> u64 {
+        self.segment.selector
+    }
+
+    /// the value and flags of all the mapped bytes of the segment
+    pub fn bytes(&self) -> impl Iterator<Item = (u64, ByteInfo)> + 'a {
+        self.id1.bytes_in_range(self.segment.address_range())
+    }
+}
+
+impl ByteType {
+    fn from_flags(flags: u32) -> Self {
+        // NOTE flags are stored without the byte value, AKA `>> 8`
+        match (flags << 8) & 0x600 {
+            0x600 => Self::Code,
+            0x400 => Self::Data,
+            0x200 => Self::Tail,
+            0x000 => Self::Unknown,
+            _ => unreachable!(),
+        }
+    }
 }
 
 impl ID1Section {
+    fn seg_by_address(&self, address: u64) -> Option<(&SegInfo, usize)> {
+        self.seglist.iter().find_map(|seg| {
+            let idx = usize::try_from(address.checked_sub(seg.offset)?).ok()?;
+            (idx < seg.data.len()).then_some((seg, idx))
+        })
+    }
+
+    /// the class of the byte at address, if the address is mapped
+    pub fn byte_type(&self, address: u64) -> Option<ByteType> {
+        self.seg_by_address(address)
+            .map(|(seg, idx)| ByteType::from_flags(seg.flags[idx]))
+    }
+
+    /// all the segments, sorted by address
+    ///
+    /// NOTE the segment name, bitness and other attributes are stored in the
+    /// `ID0`, see [crate::id0::ID0Section::segments] and
+    /// [ID1Section::segments_with_id0]
+    pub fn segments(&self) -> impl Iterator<Item = &SegInfo> {
+        let mut segs: Vec<_> = self.seglist.iter().collect();
+        segs.sort_unstable_by_key(|seg| seg.offset);
+        segs.into_iter()
+    }
+
+    /// all the `ID0` segments, with the bytes from this section
+    ///
+    /// NOTE the segments of the `ID1` are memory blocks that can contain
+    /// multiple `ID0` segments, with different bitness
+    pub fn segments_with_id0<'a>(
+        &'a self,
+        segments: &'a [Segment],
+    ) -> impl Iterator<Item = Id1Segment<'a>> {
+        segments
+            .iter()
+            .map(move |segment| Id1Segment { segment, id1: self })
+    }
+
+    /// the value and flags of the byte at address, if the address is mapped
+    pub fn byte_info(&self, address: u64) -> Option<ByteInfo> {
+        self.seg_by_address(address).map(|(seg, idx)| ByteInfo {
+            value: seg.data[idx],
+            flags: seg.flags[idx],
+        })
+    }
+
+    /// the loaded bytes of the segment that contains the address
+    ///
+    /// NOTE bytes without a value, eg. BSS, are filled with zeros, use
+    /// [ByteInfo::value] to diferentiate those from actual zeros.
+    pub fn segment_data(&self, address: u64) -> Option<Vec<u8>> {
+        let (seg, _idx) = self.seg_by_address(address)?;
+        Some(
+            seg.bytes()
+                .map(|(_addr, info)| info.value().unwrap_or(0))
+                .collect(),
+        )
+    }
+
+    /// the data of all segments, the segments without any initialized byte,
+    /// eg. BSS, return no data
+    pub fn loaded_image(&self) -> Vec<(Range<u64>, Option<Vec<u8>>)> {
+        self.segments()
+            .map(|seg| {
+                let range = seg.address_range();
+                let has_data =
+                    seg.bytes().any(|(_addr, info)| info.value().is_some());
+                let data = has_data.then(|| {
+                    seg.bytes()
+                        .map(|(_addr, info)| info.value().unwrap_or(0))
+                        .collect()
+                });
+                (range, data)
+            })
+            .collect()
+    }
+
+    /// the value and flags of all mapped bytes in the range
+    pub fn bytes_in_range(
+        &self,
+        range: Range<u64>,
+    ) -> impl Iterator<Item = (u64, ByteInfo)> + '_ {
+        self.segments().flat_map(move |seg| {
+            let seg_range = seg.address_range();
+            let start = range.start.clamp(seg_range.start, seg_range.end);
+            let end = range.end.clamp(seg_range.start, seg_range.end);
+            let start = (start - seg.offset) as usize;
+            let end = (end - seg.offset) as usize;
+            seg.bytes().skip(start).take(end.saturating_sub(start))
+        })
+    }
+
     pub(crate) fn read(
         input: &mut impl IdaGenericUnpack,
         header: &IDBHeader,
@@ -191,13 +401,12 @@ impl ID1Section {
                             std::cmp::Ordering::Equal => {}
                         }
                         let len = seg.address.end - seg.address.start;
-                        let (data, _flags) =
-                            split_flags_data(&mut *input, len)?;
+                        let (data, flags) = split_flags_data(&mut *input, len)?;
                         current_offset += len * 4;
                         Ok(SegInfo {
                             offset: seg.address.start,
                             data,
-                            _flags,
+                            flags,
                         })
                     })
                     .collect::<Result<_>>()?
@@ -206,14 +415,14 @@ impl ID1Section {
                 // the data for the segments are stored sequentialy in disk
                 segs.into_iter()
                     .map(|address| {
-                        let (data, _flags) = split_flags_data(
+                        let (data, flags) = split_flags_data(
                             &mut *input,
                             address.end - address.start,
                         )?;
                         Ok(SegInfo {
                             offset: address.start,
                             data,
-                            _flags,
+                            flags,
                         })
                     })
                     .collect::<Result<_>>()?
@@ -0,0 +1,150 @@
+use anyhow::{anyhow, ensure, Result};
+
+use std::ops::Range;
+
+use crate::id1::{ByteInfo, ByteType};
+use crate::ida_reader::{IdaGenericUnpack, IdaUnpack, IdaUnpacker};
+use crate::{IDBHeader, IDBSectionCompression};
+
+/// the ID2 section, AKA sparse flags, used by newer versions of IDA to store
+/// the byte flags of big segments with mostly uninitialized bytes, eg. `.bss`,
+/// instead of storing them in the ID1.
+///
+/// NOTE the ID1 don't contain the bytes of the ranges stored in the ID2, so
+/// any address is found in at most one of them, the ID2 never overrides the
+/// ID1. To find a byte, query [crate::id1::ID1Section::byte_info] and fallback
+/// to [ID2Section::byte_by_address].
+#[derive(Clone, Debug)]
+pub struct ID2Section {
+    /// the address ranges stored in this section, sorted by address
+    pub ranges: Vec<Range<u64>>,
+    /// only the bytes with non-default flags, sorted by address
+    entries: Vec<SparseFlags>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct SparseFlags {
+    address: u64,
+    /// raw flags, including the value in the lower 8 bits
+    flags: u32,
+    /// size of the item for code/data heads, 0 otherwise
+    len: u64,
+}
+
+impl ID2Section {
+    pub(crate) fn read(
+        input: &mut impl IdaGenericUnpack,
+        header: &IDBHeader,
+        compress: IDBSectionCompression,
+    ) -> Result<Self> {
+        match compress {
+            IDBSectionCompression::None => Self::read_inner(input, header),
+            IDBSectionCompression::Zlib => {
+                let mut input = flate2::read::ZlibDecoder::new(input);
+                Self::read_inner(&mut input, header)
+            }
+        }
+    }
+
+    fn read_inner(
+        input: &mut impl IdaGenericUnpack,
+        header: &IDBHeader,
+    ) -> Result<Self> {
+        let mut magic = [0; 8];
+        input.read_exact(&mut magic)?;
+        ensure!(
+            &magic == b"IDAS\x1d\xa5\x55\x55",
+            "Invalid ID2 section magic"
+        );
+        let is_64 = header.magic_version.is_64();
+        let mut input = IdaUnpacker::new(input, is_64);
+        let bad_address = if is_64 { u64::MAX } else { u32::MAX.into() };
+
+        // TODO the meaning of this value is unknown, always 0
+        let _unknown1 = input.unpack_dd()?;
+        #[cfg(feature = "restrictive")]
+        ensure!(_unknown1 == 0, "Invalid ID2 unknown value {_unknown1:X}");
+        let ranges_len = input.unpack_dd()?;
+        // each range start is relative to the end of the previous one
+        let mut last_end = 0u64;
+        let ranges = (0..ranges_len)
+            .map(|_| {
+                let start = last_end
+                    .checked_add(input.unpack_usize()?)
+                    .ok_or_else(|| anyhow!("Invalid ID2 range"))?;
+                let end = start
+                    .checked_add(input.unpack_usize()?)
+                    .ok_or_else(|| anyhow!("Invalid ID2 range"))?;
+                last_end = end;
+                Ok(start..end)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // TODO the meaning of this value is unknown, always 0
+        let _unknown2 = input.unpack_dd()?;
+        #[cfg(feature = "restrictive")]
+        ensure!(_unknown2 == 0, "Invalid ID2 unknown value {_unknown2:X}");
+
+        // each address is relative to the previous one, the list ends with
+        // an address equal to BADADDR
+        let mut entries: Vec<SparseFlags> = vec![];
+        let mut address = 0u64;
+        loop {
+            address = address.wrapping_add(input.unpack_usize()?);
+            if !is_64 {
+                address &= u64::from(u32::MAX);
+            }
+            if address == bad_address {
+                break;
+            }
+            ensure!(
+                entries.last().is_none_or(|last| last.address < address),
+                "Invalid ID2 entry address {address:X}"
+            );
+            let flags = input.unpack_dd()?;
+            let len = input.unpack_usize()?;
+            #[cfg(feature = "restrictive")]
+            ensure!(
+                ranges.iter().any(|range| range.contains(&address)),
+                "ID2 entry {address:X} outside the section ranges"
+            );
+            entries.push(SparseFlags {
+                address,
+                flags,
+                len,
+            });
+        }
+
+        #[cfg(feature = "restrictive")]
+        ensure!(
+            std::io::Read::read(&mut input, &mut [0])? == 0,
+            "Unexpected data after the ID2 entries"
+        );
+
+        Ok(Self { ranges, entries })
+    }
+
+    /// the value and flags of the byte at address, if the address is in one
+    /// of the [ID2Section::ranges]
+    ///
+    /// NOTE bytes that are not explicitly stored are either the tail of the
+    /// previous item or unknown bytes without value.
+    pub fn byte_by_address(&self, address: u64) -> Option<ByteInfo> {
+        if !self.ranges.iter().any(|range| range.contains(&address)) {
+            return None;
+        }
+        let idx = self.entries.partition_point(|e| e.address <= address);
+        let before = &self.entries[..idx];
+        if let Some(entry) = before.last().filter(|e| e.address == address) {
+            return Some(ByteInfo::from_raw(entry.flags));
+        }
+        // tails can only be part of the last head
+        let head = before.iter().rev().find(|e| {
+            ByteInfo::from_raw(e.flags).byte_type() != ByteType::Tail
+        });
+        let is_tail =
+            head.is_some_and(|head| address - head.address < head.len);
+        // FF_TAIL without value or FF_UNK without value
+        Some(ByteInfo::from_raw(if is_tail { 0x200 } else { 0 }))
+    }
+}
@@ -34,7 +34,7 @@ pub trait IdaUnpack: IdaGenericUnpack {
         if self.is_64() {
             self.unpack_dq()
         } else {
-            self.unpack_dd_ext_max().map(u64::from)
+            self.unpack_dd_ext_max()
         }
     }
 
@@ -141,10 +141,12 @@ pub trait IdaGenericBufUnpack: IdaGenericUnpack + BufRead {
         let flags = self.read_u32()?;
         if flags == 0x7fff_fffe {
             let len = self.read_u32()?;
-            let mut data = vec![0; 8 + len as usize];
-            data[0..4].copy_from_slice(&flags.to_le_bytes());
-            data[4..8].copy_from_slice(&len.to_le_bytes());
-            self.read_exact(&mut data[8..])?;
+            let mut data = flags.to_le_bytes().to_vec();
+            data.extend(len.to_le_bytes());
+            // don't trust len for the allocation, it could be anything
+            let read =
+                Read::take(&mut *self, len.into()).read_to_end(&mut data)?;
+            ensure!(read == len as usize, "Unexpected EoF on TIL type");
             Ok(data)
         } else {
             let mut data = flags.to_le_bytes().to_vec();
@@ -563,7 +565,8 @@ pub trait IdaGenericUnpack: Read {
             //SEG = 1
             value @ ..=0x7F => value.into(),
         };
-        Ok(value - 1)
+        // only possible with a following 0 value, in non-restrictive mode
+        Ok(value.saturating_sub(1))
     }
 
     fn serialize_dt(value: u16) -> Result<Vec<u8>> {
@@ -652,10 +655,10 @@ pub trait IdaGenericUnpack: Read {
         let loop_cnt = self.read_dt()?;
         let extended = (0..loop_cnt)
             .map(|_| {
-                let _value1 = self.unpack_dt_bytes()?;
-                let _value2 = self.unpack_dt_bytes()?;
+                let key = self.unpack_dt_bytes()?;
+                let value = self.unpack_dt_bytes()?;
                 // TODO maybe more...
-                Ok(TypeAttributeExt { _value1, _value2 })
+                Ok(TypeAttributeExt { key, value })
             })
             .collect::<Result<_>>()?;
         Ok(TypeAttribute {
@@ -1,8 +1,10 @@
 #[forbid(unsafe_code)]
 pub mod id0;
 pub mod id1;
+pub mod id2;
 pub(crate) mod ida_reader;
 pub mod nam;
+pub mod packing;
 pub mod til;
 
 use std::borrow::Cow;
@@ -17,10 +19,15 @@ use ida_reader::IdbReader;
 use serde::Deserialize;
 
 use crate::id1::ID1Section;
+use crate::id2::ID2Section;
 use crate::nam::NamSection;
 use crate::til::section::TILSection;
 use anyhow::{anyhow, ensure, Result};
 
+/// reader for a database already in memory, it implements all the traits
+/// required by [IDBParser]
+pub type SliceReader<'a> = std::io::Cursor<&'a [u8]>;
+
 #[derive(Debug, Clone, Copy)]
 pub struct IDBParser<I> {
     input: I,
@@ -52,6 +59,10 @@ impl_idb_offset!(ID0Offset);
 pub struct ID1Offset(NonZeroU64);
 impl_idb_offset!(ID1Offset);
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ID2Offset(NonZeroU64);
+impl_idb_offset!(ID2Offset);
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct NamOffset(NonZeroU64);
 impl_idb_offset!(NamOffset);
@@ -60,12 +71,120 @@ impl_idb_offset!(NamOffset);
 pub struct TILOffset(NonZeroU64);
 impl_idb_offset!(TILOffset);
 
+/// Location and compression of a section, read without decompressing it
+#[derive(Debug, Clone, Copy)]
+pub struct IDBSectionInfo {
+    /// offset of the section header in the file
+    pub offset: u64,
+    /// size of the section data in the file, after the header
+    pub len: u64,
+    pub compression: IDBSectionCompression,
+}
+
+/// The sections present in the IDB file
+#[derive(Debug, Clone, Copy)]
+pub struct IDBSectionManifest {
+    pub id0: Option<IDBSectionInfo>,
+    pub id1: Option<IDBSectionInfo>,
+    pub id2: Option<IDBSectionInfo>,
+    pub nam: Option<IDBSectionInfo>,
+    pub til: Option<IDBSectionInfo>,
+}
+
+/// Something unexpected found while parsing, that was recovered from
+#[derive(Debug, Clone)]
+pub enum Warning {
+    /// B-tree page that could not be read, its entries are missing
+    ID0SkippedPage(id0::ID0SkippedPage),
+    /// B-tree entries were out of order and had to be sorted
+    ID0UnsortedEntries,
+    /// part of the TIL section data was not parsed
+    TILUnparsedData(&'static str),
+    /// TIL file dependency that could not be found
+    TILMissingDependency(IDBString),
+    /// flags with bits set that have no known meaning
+    UnknownFlagBits { field: &'static str, bits: u64 },
+}
+
+impl std::fmt::Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Warning::ID0SkippedPage(page) => {
+                write!(f, "Skipped ID0 page {}: {}", page.page, page.error)
+            }
+            Warning::ID0UnsortedEntries => {
+                f.write_str("ID0 entries were out of order")
+            }
+            Warning::TILUnparsedData(msg) => f.write_str(msg),
+            Warning::TILMissingDependency(name) => {
+                write!(f, "{}: No such file or directory", name.as_utf8_lossy())
+            }
+            Warning::UnknownFlagBits { field, bits } => {
+                write!(f, "Unknown {field} bits {bits:#x}")
+            }
+        }
+    }
+}
+
+/// Warnings collected while parsing a section
+#[derive(Debug, Clone, Default)]
+pub struct ParseReport {
+    pub warnings: Vec<Warning>,
+}
+
+impl ParseReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.warnings.is_empty()
+    }
+
+    /// error if `restrictive`, otherwise record the warning and continue
+    pub(crate) fn ensure(
+        &mut self,
+        condition: bool,
+        warning: impl FnOnce() -> Warning,
+    ) -> Result<()> {
+        if condition {
+            return Ok(());
+        }
+        let warning = warning();
+        #[cfg(feature = "restrictive")]
+        return Err(anyhow!("{warning}"));
+        #[cfg(not(feature = "restrictive"))]
+        {
+            self.warnings.push(warning);
+            Ok(())
+        }
+    }
+}
+
 impl<I: IdbReader> IDBParser<I> {
+    /// read the IDB header, the input is rewind to the start of the file
+    /// before reading, and left right after the header
     pub fn new(mut input: I) -> Result<Self> {
+        input.seek(SeekFrom::Start(0))?;
         let header = IDBHeader::read(&mut input)?;
         Ok(Self { input, header })
     }
 
+    /// return the input, the position is unspecified after reading any
+    /// section
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+
+    /// if the database is for a 64 bits program, AKA a `.i64` file
+    pub fn is_64(&self) -> bool {
+        self.header.magic_version.is_64()
+    }
+
+    pub fn version(&self) -> IDBVersion {
+        self.header.version
+    }
+
     pub fn id0_section_offset(&self) -> Option<ID0Offset> {
         self.header.id0_offset.map(ID0Offset)
     }
@@ -74,6 +193,18 @@ impl<I: IdbReader> IDBParser<I> {
         self.header.id1_offset.map(ID1Offset)
     }
 
+    /// only available in newer versions of the database
+    pub fn id2_section_offset(&self) -> Option<ID2Offset> {
+        match self.header.data {
+            IDBHeaderVersion::V6 { id2_offset, .. } => {
+                id2_offset.map(ID2Offset)
+            }
+            IDBHeaderVersion::V1 { .. }
+            | IDBHeaderVersion::V4 { .. }
+            | IDBHeaderVersion::V5 { .. } => None,
+        }
+    }
+
     pub fn nam_section_offset(&self) -> Option<NamOffset> {
         self.header.nam_offset.map(NamOffset)
     }
@@ -82,6 +213,47 @@ impl<I: IdbReader> IDBParser<I> {
         self.header.til_offset.map(TILOffset)
     }
 
+    /// read only the section header, not the content
+    pub fn section_info(
+        &mut self,
+        offset: impl IDBOffset,
+    ) -> Result<IDBSectionInfo> {
+        let offset = offset.idb_offset();
+        self.input.seek(SeekFrom::Start(offset))?;
+        let header = IDBSectionHeader::read(&self.header, &mut self.input)?;
+        Ok(IDBSectionInfo {
+            offset,
+            len: header.len,
+            compression: header.compress,
+        })
+    }
+
+    /// read the header of all the sections in the file
+    pub fn section_manifest(&mut self) -> Result<IDBSectionManifest> {
+        Ok(IDBSectionManifest {
+            id0: self
+                .id0_section_offset()
+                .map(|offset| self.section_info(offset))
+                .transpose()?,
+            id1: self
+                .id1_section_offset()
+                .map(|offset| self.section_info(offset))
+                .transpose()?,
+            id2: self
+                .id2_section_offset()
+                .map(|offset| self.section_info(offset))
+                .transpose()?,
+            nam: self
+                .nam_section_offset()
+                .map(|offset| self.section_info(offset))
+                .transpose()?,
+            til: self
+                .til_section_offset()
+                .map(|offset| self.section_info(offset))
+                .transpose()?,
+        })
+    }
+
     pub fn read_id0_section(&mut self, id0: ID0Offset) -> Result<ID0Section> {
         read_section(
             &mut self.input,
@@ -91,6 +263,27 @@ impl<I: IdbReader> IDBParser<I> {
         )
     }
 
+    /// same as [IDBParser::read_id0_section], also reporting the skipped pages
+    /// and the out of order entries
+    pub fn read_id0_section_with_report(
+        &mut self,
+        id0: ID0Offset,
+    ) -> Result<(ID0Section, ParseReport)> {
+        let section = self.read_id0_section(id0)?;
+        let mut report = ParseReport {
+            warnings: section
+                .skipped_pages()
+                .iter()
+                .cloned()
+                .map(Warning::ID0SkippedPage)
+                .collect(),
+        };
+        if section.is_reordered() {
+            report.warnings.push(Warning::ID0UnsortedEntries);
+        }
+        Ok((section, report))
+    }
+
     pub fn read_id1_section(&mut self, id1: ID1Offset) -> Result<ID1Section> {
         read_section(
             &mut self.input,
@@ -100,6 +293,15 @@ impl<I: IdbReader> IDBParser<I> {
         )
     }
 
+    pub fn read_id2_section(&mut self, id2: ID2Offset) -> Result<ID2Section> {
+        read_section(
+            &mut self.input,
+            &self.header,
+            id2.0.get(),
+            ID2Section::read,
+        )
+    }
+
     pub fn read_nam_section(&mut self, nam: NamOffset) -> Result<NamSection> {
         read_section(
             &mut self.input,
@@ -118,6 +320,23 @@ impl<I: IdbReader> IDBParser<I> {
         )
     }
 
+    /// same as [IDBParser::read_til_section], also returning the warnings
+    pub fn read_til_section_with_report(
+        &mut self,
+        til: TILOffset,
+    ) -> Result<(TILSection, ParseReport)> {
+        let mut report = ParseReport::new();
+        let section = read_section(
+            &mut self.input,
+            &self.header,
+            til.0.get(),
+            |input, _header, compressed| {
+                TILSection::read_with_report(input, compressed, &mut report)
+            },
+        )?;
+        Ok((section, report))
+    }
+
     pub fn decompress_section(
         &mut self,
         offset: impl IDBOffset,
@@ -154,6 +373,20 @@ impl<I: IdbReader> IDBParser<I> {
             std::io::Read::take(&mut self.input, section_header.len);
         TILSection::decompress(&mut input, output, section_header.compress)
     }
+    /// read only the header of the TIL section, see
+    /// [TILSection::read_header_only]
+    pub fn read_til_section_header(
+        &mut self,
+        til: TILOffset,
+    ) -> Result<til::section::TILSectionHeader> {
+        self.input.seek(SeekFrom::Start(til.0.get()))?;
+        let section_header =
+            IDBSectionHeader::read(&self.header, &mut self.input)?;
+        // makes sure the reader doesn't go out-of-bounds
+        let mut input =
+            std::io::Read::take(&mut self.input, section_header.len);
+        TILSection::read_header_only(&mut input, section_header.compress)
+    }
 }
 
 fn read_section<'a, I, T, F>(
@@ -190,6 +423,25 @@ where
     Ok(result)
 }
 
+/// compress the section data, the result can be read back by `read_section`
+#[cfg(test)]
+pub(crate) fn compress_section(
+    data: &[u8],
+    compress: IDBSectionCompression,
+) -> Result<Vec<u8>> {
+    match compress {
+        IDBSectionCompression::None => Ok(data.to_vec()),
+        IDBSectionCompression::Zlib => {
+            let mut output = flate2::write::ZlibEncoder::new(
+                vec![],
+                flate2::Compression::default(),
+            );
+            std::io::Write::write_all(&mut output, data)?;
+            Ok(output.finish()?)
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum IDBMagic {
     IDA0,
@@ -219,8 +471,9 @@ impl IDBMagic {
     }
 }
 
+/// version of the IDB file header
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum IDBVersion {
+pub enum IDBVersion {
     // TODO add other versions
     V1,
     V4,
@@ -285,6 +538,30 @@ impl TryFrom<u8> for IDBSectionCompression {
     }
 }
 
+/// guess the compression of the section data from the first bytes
+///
+/// data that is not compressed is reported as [IDBSectionCompression::None],
+/// an error is returned only if the data uses a compression not supported by
+/// this crate, eg. zstd.
+pub fn detect_compression(data: &[u8]) -> Result<IDBSectionCompression> {
+    match data {
+        // zstd frame magic
+        [0x28, 0xB5, 0x2F, 0xFD, ..] => {
+            Err(anyhow!("Zstd compression is not supported"))
+        }
+        // zlib header: deflate method with a window up to 32K, the check
+        // bits make the header a multiple of 31
+        [cmf, flg, ..]
+            if cmf & 0x0F == 8
+                && cmf >> 4 <= 7
+                && u16::from_be_bytes([*cmf, *flg]) % 31 == 0 =>
+        {
+            Ok(IDBSectionCompression::Zlib)
+        }
+        _ => Ok(IDBSectionCompression::None),
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct IDBHeaderRaw {
     magic: [u8; 4],
@@ -563,18 +840,30 @@ impl VaVersion {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Eq)]
 pub struct IDBString(Vec<u8>);
 
 impl IDBString {
+    /// create the string with all the bytes, including any `\x00`
     pub fn new(data: Vec<u8>) -> Self {
         Self(data)
     }
 
-    pub fn as_utf8_lossy(&self) -> Cow<str> {
+    /// create the string with the bytes up to the first `\x00`, if any
+    pub fn from_nul_terminated(data: &[u8]) -> Self {
+        let end = data.iter().position(|b| *b == 0).unwrap_or(data.len());
+        Self(data[..end].to_vec())
+    }
+
+    pub fn as_utf8_lossy(&self) -> Cow<'_, str> {
         String::from_utf8_lossy(&self.0)
     }
 
+    /// owned version of [IDBString::as_utf8_lossy]
+    pub fn to_string_lossy(&self) -> String {
+        self.as_utf8_lossy().into_owned()
+    }
+
     pub fn as_bytes(&self) -> &[u8] {
         &self.0
     }
@@ -582,6 +871,52 @@ impl IDBString {
     pub fn into_inner(self) -> Vec<u8> {
         self.0
     }
+
+    /// the string as a quoted IDC string literal, escaping the quotes,
+    /// backslashes and any byte that is not printable ASCII
+    pub fn to_idc_literal(&self) -> String {
+        let mut output = String::with_capacity(self.0.len() + 2);
+        output.push('"');
+        for byte in &self.0 {
+            match byte {
+                b'"' => output.push_str("\\\""),
+                b'\\' => output.push_str("\\\\"),
+                b'\n' => output.push_str("\\n"),
+                b'\r' => output.push_str("\\r"),
+                b'\t' => output.push_str("\\t"),
+                0x20..=0x7E => output.push(char::from(*byte)),
+                // octal escapes have a fixed len, unlike `\x` that could
+                // consume the next char if it's a hex digit
+                _ => output.push_str(&format!("\\{byte:03o}")),
+            }
+        }
+        output.push('"');
+        output
+    }
+}
+
+/// append bytes to the string, allowing it to be built with [write!]
+impl std::io::Write for IDBString {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl PartialEq<str> for IDBString {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other.as_bytes()
+    }
+}
+
+impl PartialEq<&str> for IDBString {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == other.as_bytes()
+    }
 }
 
 impl std::fmt::Debug for IDBString {
@@ -610,6 +945,22 @@ mod test {
     use std::io::{BufReader, Seek};
     use std::path::{Path, PathBuf};
 
+    fn gcc_til() -> TILSection {
+        let mut input =
+            BufReader::new(File::open("resources/tils/gcc.til").unwrap());
+        TILSection::read(&mut input, IDBSectionCompression::None).unwrap()
+    }
+
+    impl til::Type {
+        fn from_variant(type_variant: til::TypeVariant) -> Self {
+            Self {
+                is_const: false,
+                is_volatile: false,
+                type_variant,
+            }
+        }
+    }
+
     #[test]
     fn parse_id0_til() {
         let function = [
@@ -778,6 +1129,372 @@ mod test {
         let _til = til::Type::new_from_id0(&function, vec![]).unwrap();
     }
 
+    #[test]
+    fn type_unresolved_forward_ref() {
+        let til = gcc_til();
+        let struct_ref = |name: &[u8]| {
+            let mut data = vec![
+                0x0d, // struct type
+                0x01, // struct ref
+                u8::try_from(name.len() + 1).unwrap(),
+            ];
+            data.extend_from_slice(name);
+            data.push(0x00);
+            til::Type::new_from_id0(&data, vec![]).unwrap()
+        };
+        // a forward declared struct, never defined
+        let missing = struct_ref(b"__missing_struct");
+        assert!(!missing.is_fully_resolved(&til));
+
+        // struct dylib_command {uint32_t cmd;uint32_t cmdsize;dylib dylib;};
+        let (idx, defined) = til
+            .types
+            .iter()
+            .enumerate()
+            .find(|(_, ty)| ty.name.as_bytes() == b"dylib_command")
+            .unwrap();
+        let til::TypeVariant::Struct(til_struct) = &defined.tinfo.type_variant
+        else {
+            panic!("dylib_command is not a struct");
+        };
+        let mut solver = til::TILTypeSizeSolver::new(&til);
+        let mut offset = 0;
+        let members: Vec<_> = til_struct
+            .members
+            .iter()
+            .map(|member| {
+                let size =
+                    solver.type_size_bytes(None, &member.member_type).unwrap();
+                let name = member.name.as_ref().unwrap().to_string_lossy();
+                let member_offset = offset;
+                offset += size;
+                (name, member_offset, size)
+            })
+            .collect();
+        assert_eq!(
+            members,
+            [
+                ("cmd".to_string(), 0, 4),
+                ("cmdsize".to_string(), 4, 4),
+                ("dylib".to_string(), 8, 0x10),
+            ]
+        );
+        assert_eq!(
+            solver.type_size_bytes(Some(idx), &defined.tinfo),
+            Some(0x18)
+        );
+        // the dylib member is a typeref to the dylib struct
+        assert!(defined.tinfo.is_fully_resolved(&til));
+        assert!(struct_ref(b"dylib_command").is_fully_resolved(&til));
+
+        // struct X {IUnknown x;}; IUnknown is only forward declared
+        let forward = til.get_name(b"X").unwrap();
+        let til::TypeVariant::Struct(til_struct) = &forward.tinfo.type_variant
+        else {
+            panic!("X is not a struct");
+        };
+        assert_eq!(til_struct.members.len(), 1);
+        assert!(!forward.tinfo.is_fully_resolved(&til));
+        assert!(!struct_ref(b"X").is_fully_resolved(&til));
+    }
+
+    #[test]
+    fn calling_convention_names() {
+        use crate::til::function::CallingConvention;
+        let name = |cm| {
+            CallingConvention::from_cm_byte(cm).map(CallingConvention::as_str)
+        };
+        assert_eq!(name(0x00), None);
+        assert_eq!(name(0x10), None);
+        assert_eq!(name(0xA0), None);
+        assert_eq!(name(0x20), Some("voidarg"));
+        assert_eq!(name(0x30), Some("cdecl"));
+        assert_eq!(name(0xB0), Some("golang"));
+        assert_eq!(name(0xC0), Some("ccreserved3"));
+    }
+
+    #[test]
+    fn resolve_typeref_chain() {
+        let mut til = gcc_til();
+        let typeref = |name: &str| {
+            til::Type::from_variant(til::TypeVariant::Typeref(til::Typeref {
+                ref_type: None,
+                typeref_value: til::TyperefValue::UnsolvedName(Some(
+                    IDBString::new(name.as_bytes().to_vec()),
+                )),
+            }))
+        };
+        let add_type = |til: &mut TILSection, name: &str, tinfo| {
+            til.types.push(til::TILTypeInfo::new(
+                IDBString::new(name.as_bytes().to_vec()),
+                0,
+                tinfo,
+            ))
+        };
+        let til::TypeVariant::Typeref(first) = typeref("first").type_variant
+        else {
+            unreachable!();
+        };
+        // first -> second -> void
+        add_type(&mut til, "first", typeref("second"));
+        add_type(
+            &mut til,
+            "second",
+            til::Type::from_variant(til::TypeVariant::Basic(til::Basic::Void)),
+        );
+        let resolved = til.resolve_typeref(&first).unwrap();
+        assert_eq!(resolved.name, "second");
+
+        // a self referential typedef
+        add_type(&mut til, "self_ref", typeref("self_ref"));
+        let til::TypeVariant::Typeref(self_ref) =
+            typeref("self_ref").type_variant
+        else {
+            unreachable!();
+        };
+        assert!(til.resolve_typeref(&self_ref).is_none());
+    }
+
+    #[test]
+    fn dangling_ordinal_alias() {
+        let mut til = gcc_til();
+        let existing = til.types[0].ordinal;
+        let aliases = til.header.type_ordinal_alias.get_or_insert_default();
+        // alias to a missing ordinal, an alias to an alias and a cycle
+        aliases.push((0xFFFF_0000, 0xFFFF_0001));
+        aliases.push((0xFFFF_0002, existing.try_into().unwrap()));
+        aliases.push((0xFFFF_0003, 0xFFFF_0002));
+        aliases.push((0xFFFF_0004, 0xFFFF_0005));
+        aliases.push((0xFFFF_0005, 0xFFFF_0004));
+        let ord = |ord| id0::Id0TilOrd { ord };
+        assert!(matches!(
+            til.get_by_ordinal(0xFFFF_0000),
+            Some(til::section::TILOrdType::Alias {
+                target: 0xFFFF_0001
+            })
+        ));
+        assert_eq!(til.get_ord_idx(ord(0xFFFF_0000)), None);
+        assert_eq!(til.get_ord_idx(ord(0xFFFF_0003)), Some(0));
+        assert_eq!(til.get_ord_idx(ord(0xFFFF_0004)), None);
+    }
+
+    #[test]
+    fn til_dependencies() {
+        let mut til = gcc_til();
+        let name = til.types[0].name.clone();
+        til.types.clear();
+        til.header.dependencies = vec![
+            IDBString::new(b"gcc".to_vec()),
+            IDBString::new(b"missing".to_vec()),
+        ];
+        let (dependencies, report) = til
+            .load_dependencies_with_report(&["resources/tils"])
+            .unwrap();
+        // the dependencies of gcc.til are also reported as missing
+        assert_eq!(dependencies.len(), 1);
+        let missing: Vec<_> = report
+            .warnings
+            .iter()
+            .map(|warning| match warning {
+                Warning::TILMissingDependency(name) => name,
+                _ => unreachable!(),
+            })
+            .collect();
+        assert_eq!(
+            missing.len(),
+            dependencies[0].header.dependencies.len() + 1
+        );
+        assert_eq!(missing.last().unwrap(), &"missing");
+
+        let typeref = til::Typeref {
+            ref_type: None,
+            typeref_value: til::TyperefValue::UnsolvedName(Some(name.clone())),
+        };
+        assert!(til.resolve_typeref(&typeref).is_none());
+        let resolved = til
+            .resolve_typeref_with_dependencies(&typeref, &dependencies)
+            .unwrap();
+        assert_eq!(resolved.name, name);
+    }
+
+    #[test]
+    fn standard_til_dirs_from() {
+        let ida_dir = std::env::temp_dir()
+            .join(format!("idb-rs-standard-til-dirs-{}", std::process::id()));
+        let til_pc = ida_dir.join("til").join("pc");
+        std::fs::create_dir_all(&til_pc).unwrap();
+        let missing = ida_dir.join("missing");
+        let dirs = til::standard_til_dirs_from([
+            ida_dir.clone(),
+            missing,
+            ida_dir.clone(),
+        ]);
+        std::fs::remove_dir_all(&ida_dir).unwrap();
+        assert_eq!(dirs, [ida_dir.join("til"), til_pc]);
+    }
+
+    #[test]
+    fn synthetic_type_size() {
+        let mut til = gcc_til();
+        let ordinal = til.types.iter().map(|ty| ty.ordinal).max().unwrap() + 1;
+        til.types.push(til::TILTypeInfo::new(
+            IDBString::new(b"synthetic_int".to_vec()),
+            ordinal,
+            til::Type::from_variant(til::TypeVariant::Basic(til::Basic::Int {
+                is_signed: Some(true),
+            })),
+        ));
+        let idx = til.types.len() - 1;
+        assert_eq!(til.get_ord_idx(id0::Id0TilOrd { ord: ordinal }), Some(idx));
+        let mut solver = til::TILTypeSizeSolver::new(&til);
+        let size = solver.type_size_bytes(Some(idx), &til.types[idx].tinfo);
+        assert_eq!(size, Some(til.header.size_int.get().into()));
+    }
+
+    #[test]
+    fn strip_pointers() {
+        use til::pointer::{Pointer, PointerModifier, PointerType};
+        let pointer = |typ, modifier| {
+            til::Type::from_variant(til::TypeVariant::Pointer(Pointer {
+                closure: PointerType::Default,
+                modifier,
+                shifted: None,
+                typ: Box::new(typ),
+            }))
+        };
+        let char = til::Type {
+            is_const: true,
+            ..til::Type::from_variant(til::TypeVariant::Basic(til::Basic::Char))
+        };
+        // `const char *__ptr32 *`
+        let ty =
+            pointer(pointer(char.clone(), Some(PointerModifier::Ptr32)), None);
+        assert_eq!(ty.strip_pointers(), (&char, 2));
+        assert_eq!(ty.pointer_depth(), 2);
+        let modifiers: Vec<_> =
+            ty.pointers().map(|pointer| pointer.modifier).collect();
+        assert_eq!(modifiers, [None, Some(PointerModifier::Ptr32)]);
+        assert_eq!(char.strip_pointers(), (&char, 0));
+    }
+
+    #[test]
+    fn usercall_argument_locations() {
+        use til::function::{ArgLoc, CallingConvention, Function, SpoiledReg};
+        let section = gcc_til();
+        let int =
+            til::Type::from_variant(til::TypeVariant::Basic(til::Basic::Int {
+                is_signed: None,
+            }));
+        let name = |name: &str| Some(IDBString::new(name.as_bytes().to_vec()));
+        // `int __usercall __spoils<ecx> f@<eax>(int a@<ecx>, int b)`
+        let function = Function {
+            calling_convention: Some(CallingConvention::Usercall),
+            ret: Box::new(int.clone()),
+            args: vec![
+                (name("a"), int.clone(), Some(ArgLoc::Reg1(1))),
+                (name("b"), int.clone(), Some(ArgLoc::Stack(4))),
+                (name("c"), int.clone(), Some(ArgLoc::None)),
+            ],
+            retloc: Some(ArgLoc::Reg1(0)),
+            spoiled: vec![
+                SpoiledReg { reg: 1, size: 4 },
+                SpoiledReg {
+                    reg: 0x40,
+                    size: 16,
+                },
+                SpoiledReg {
+                    reg: 0x1234,
+                    size: 8,
+                },
+            ],
+            method: None,
+            is_noret: false,
+            is_pure: false,
+            is_high: false,
+            is_static: false,
+            is_virtual: false,
+            is_const: false,
+            is_constructor: false,
+            is_destructor: false,
+        };
+        let ty = til::Type::from_variant(til::TypeVariant::Function(function));
+        let (data, fields) = ty.serialize(&section).unwrap();
+        let ty = til::Type::deserialize(&section, &data, &fields).unwrap();
+        let til::TypeVariant::Function(function) = &ty.type_variant else {
+            unreachable!();
+        };
+        let args: Vec<_> = function
+            .arguments()
+            .map(|arg| (arg.name().unwrap().as_bytes(), arg.location()))
+            .collect();
+        assert_eq!(
+            args,
+            [
+                (&b"a"[..], Some(&ArgLoc::Reg1(1))),
+                (b"b", Some(&ArgLoc::Stack(4))),
+                (b"c", None),
+            ]
+        );
+        assert!(function.arguments().all(|arg| *arg.ty() == int));
+        assert_eq!(function.return_location(), Some(&ArgLoc::Reg1(0)));
+        assert_eq!(function.spoiled_regs().len(), 3);
+        assert_eq!(function.spoiled_regs()[2].reg, 0x1234);
+    }
+
+    #[test]
+    fn type_attributes_extended() {
+        use til::r#enum::{Enum, EnumFormat};
+        use til::{TypeAttributeExt, TypeAttributes};
+        let section = gcc_til();
+        let attributes = TypeAttributes {
+            unknown_flags: 0,
+            extended: vec![
+                TypeAttributeExt {
+                    key: b"__org_typedef".to_vec(),
+                    value: b"my_enum_t".to_vec(),
+                },
+                TypeAttributeExt {
+                    key: b"format".to_vec(),
+                    value: vec![],
+                },
+            ],
+        };
+        let ty = til::Type::from_variant(til::TypeVariant::Enum(Enum {
+            is_signed: false,
+            is_unsigned: true,
+            output_format: EnumFormat::Hex,
+            members: vec![(Some(IDBString::new(b"A".to_vec())), 1)],
+            groups: None,
+            storage_size: None,
+            attributes: attributes.clone(),
+        }));
+        let (data, fields) = ty.serialize(&section).unwrap();
+        let ty = til::Type::deserialize(&section, &data, &fields).unwrap();
+        let result = ty.type_attributes().unwrap();
+        assert_eq!(result, &attributes);
+        assert_eq!(result.get(b"__org_typedef"), Some(&b"my_enum_t"[..]));
+        assert_eq!(result.get(b"format"), Some(&[][..]));
+        assert_eq!(result.get(b"missing"), None);
+    }
+
+    #[test]
+    fn unpack_known_values() {
+        use crate::packing::*;
+        let dd = |data: &[u8]| unpack_dd(&mut &data[..]).unwrap();
+        assert_eq!(dd(&[0x7F]), 0x7F);
+        assert_eq!(dd(&[0x81, 0x23]), 0x123);
+        assert_eq!(dd(&[0xC1, 0x23, 0x45, 0x67]), 0x0123_4567);
+        assert_eq!(dd(&[0xFF, 0x89, 0xAB, 0xCD, 0xEF]), 0x89AB_CDEF);
+        assert_eq!(unpack_dw(&mut &[0xC0, 0x12, 0x34][..]).unwrap(), 0x1234);
+        let dq = [0xFF, 0x89, 0xAB, 0xCD, 0xEF, 0x81, 0x23];
+        assert_eq!(unpack_dq(&mut &dq[..]).unwrap(), 0x123_89AB_CDEF);
+        let range = [0x81, 0x00, 0x10];
+        assert_eq!(
+            unpack_address_range(&mut &range[..], false).unwrap(),
+            0x100..0x110
+        );
+    }
+
     #[test]
     fn parse_spoiled_function_invalid_reg() {
         // ```
@@ -816,6 +1533,397 @@ mod test {
         let _parsed = id0::IDBParam::read(param, false).unwrap();
     }
 
+    #[test]
+    fn address_info_comment_kinds() {
+        fn comments(filename: &str, address: u64) -> Vec<(&str, Vec<u8>)> {
+            let file = BufReader::new(File::open(filename).unwrap());
+            let mut parser = IDBParser::new(file).unwrap();
+            let id0 = parser
+                .read_id0_section(parser.id0_section_offset().unwrap())
+                .unwrap();
+            let version = match id0.ida_info().unwrap() {
+                id0::IDBParam::V1(x) => x.version,
+                id0::IDBParam::V2(x) => x.version,
+            };
+            id0.address_info(version)
+                .unwrap()
+                .map(Result::unwrap)
+                .filter(|(addr, _info)| *addr == address)
+                .filter_map(|(_addr, info)| match info {
+                    id0::AddressInfo::Comment(comment) => {
+                        let kind = match comment {
+                            id0::Comments::Comment(_) => "comment",
+                            id0::Comments::RepeatableComment(_) => "repeatable",
+                            id0::Comments::PreComment(_) => "pre",
+                            id0::Comments::PostComment(_) => "post",
+                        };
+                        Some((kind, comment.message().to_vec()))
+                    }
+                    _ => None,
+                })
+                .collect()
+        }
+        assert_eq!(
+            comments("resources/idbs/func_comment.idb", 0x1),
+            [
+                ("pre", b"anterior line 1".to_vec()),
+                ("pre", b"anterior line 2".to_vec()),
+                ("post", b"posterior line 1".to_vec()),
+                ("post", b"posterior line 2".to_vec()),
+            ]
+        );
+        assert_eq!(
+            comments("resources/idbs/gcc.i64", 0x1_0000_0000),
+            [
+                ("comment", b"Magic number".to_vec()),
+                ("pre", b"; Mach-o header".to_vec()),
+            ]
+        );
+    }
+
+    #[test]
+    fn address_above_u32_on_32bits() {
+        let file = BufReader::new(
+            File::open("resources/idbs/FlawedGrace.idb").unwrap(),
+        );
+        let mut parser = IDBParser::new(file).unwrap();
+        let id0 = parser
+            .read_id0_section(parser.id0_section_offset().unwrap())
+            .unwrap();
+        let address = 0x1_0000_0000u64;
+        assert_eq!(id0.label_at(address).unwrap(), None);
+        assert_eq!(id0.comment(address).unwrap(), None);
+        assert_eq!(id0.comment_repeatable(address).unwrap(), None);
+        assert_eq!(id0.comments_pre(address).count(), 0);
+        assert_eq!(id0.comments_post(address).count(), 0);
+        assert_eq!(id0.address_info_at(address).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn id2_sparse_flags() {
+        use id1::ByteType;
+        let read_id2 = |filename| {
+            let file = BufReader::new(File::open(filename).unwrap());
+            let mut parser = IDBParser::new(file).unwrap();
+            parser
+                .read_id2_section(parser.id2_section_offset().unwrap())
+                .unwrap()
+        };
+
+        let id2 = read_id2("resources/idbs/madame.i64");
+        assert_eq!(id2.ranges, vec![0x408060..0x408508]);
+        let byte = |addr| id2.byte_by_address(addr).unwrap();
+        // qword data with a name, outside the item no value
+        assert_eq!(byte(0x408060).as_raw(), 0x3000_5c00);
+        assert_eq!(byte(0x408060).byte_type(), ByteType::Data);
+        assert_eq!(byte(0x408060).value(), None);
+        assert_eq!(byte(0x408067).as_raw(), 0x200);
+        assert_eq!(byte(0x408067).byte_type(), ByteType::Tail);
+        // align directive
+        assert_eq!(byte(0x408069).as_raw(), 0xb000_0400);
+        // word with an initialized value of 0, and explicit tails
+        assert_eq!(byte(0x408490).as_raw(), 0x1000_5700);
+        assert_eq!(byte(0x408490).value(), Some(0));
+        assert_eq!(byte(0x408491).as_raw(), 0x300);
+        assert_eq!(byte(0x408491).value(), Some(0));
+        assert_eq!(byte(0x408507).byte_type(), ByteType::Tail);
+        assert!(id2.byte_by_address(0x40805f).is_none());
+        assert!(id2.byte_by_address(0x408508).is_none());
+
+        let id2 = read_id2("resources/idbs/FlawedGrace.idb");
+        assert_eq!(id2.ranges, [0x46b60a..0x46c000, 0x46cf48..0x470000]);
+        let byte = |addr| id2.byte_by_address(addr).unwrap();
+        assert_eq!(byte(0x46b60a).as_raw(), 0xb000_0500);
+        assert_eq!(byte(0x46b60b).as_raw(), 0x300);
+        assert!(id2.byte_by_address(0x46c000).is_none());
+    }
+
+    #[test]
+    fn function_tail_layouts() {
+        for (filename, version, tails_len, first, owner, refqty) in [
+            (
+                "resources/idbs/FlawedGrace.idb",
+                700,
+                132,
+                0x402910..0x402a83,
+                0x4024e0,
+                1,
+            ),
+            (
+                "resources/idbs/ComRAT-Orchestrator.i64",
+                700,
+                314,
+                0x1_8000_2490..0x1_8000_249b,
+                0x1_8005_0de0,
+                2,
+            ),
+            (
+                "resources/idbs/madame.i64",
+                830,
+                4,
+                0x401280..0x4012b1,
+                0x4012f0,
+                1,
+            ),
+            (
+                "resources/idbs/y0da-new.i64",
+                830,
+                11104,
+                0x1_8000_1011..0x1_8000_101e,
+                0x1_8002_6aea,
+                1,
+            ),
+        ] {
+            let file = BufReader::new(File::open(filename).unwrap());
+            let mut parser = IDBParser::new(file).unwrap();
+            let id0 = parser
+                .read_id0_section(parser.id0_section_offset().unwrap())
+                .unwrap();
+            assert_eq!(id0.ida_info().unwrap().version(), version);
+            let tails: Vec<_> = id0
+                .functions()
+                .unwrap()
+                .map(Result::unwrap)
+                .filter(|function| function.flags().is_tail())
+                .collect();
+            assert_eq!(tails.len(), tails_len);
+            assert!(tails.iter().all(|tail| tail.extra.is_some()));
+            assert_eq!(tails[0].address, first);
+            let Some(id0::IDBFunctionExtra::Tail {
+                owner: first_owner,
+                refqty: first_refqty,
+            }) = tails[0].extra
+            else {
+                panic!("{filename} first tail has no owner");
+            };
+            assert_eq!(first_owner, owner);
+            assert_eq!(first_refqty, refqty);
+        }
+    }
+
+    #[test]
+    fn function_containing_invalid_owner() {
+        let file = BufReader::new(
+            File::open("resources/idbs/FlawedGrace.idb").unwrap(),
+        );
+        let mut parser = IDBParser::new(file).unwrap();
+        let id0 = parser
+            .read_id0_section(parser.id0_section_offset().unwrap())
+            .unwrap();
+        let funcs = id0.netnode_idx_by_name("$ funcs").unwrap().unwrap();
+        let start = 0x8000_0000u32;
+        let mut key = vec![b'.'];
+        key.extend(u32::try_from(funcs).unwrap().to_be_bytes());
+        key.push(b'S');
+        key.extend(start.to_be_bytes());
+        // BADADDR and an owner above u32::MAX
+        for owner_offset in [-0x7FFF_FFFFi32, i32::MIN] {
+            let mut value = vec![0xFF];
+            value.extend(start.to_be_bytes());
+            // len, tail flags
+            value.extend([0x10, 0xC0, 0x80, 0x00, 0xFF]);
+            value.extend(owner_offset.to_be_bytes());
+            // refqty, full flags
+            value.extend([0x01, 0xC0, 0x00, 0x80, 0x00, 0x00]);
+            let mut id0 = id0.clone();
+            id0.entries.push(id0::ID0Entry {
+                key: key.clone(),
+                value,
+            });
+            id0.sort_entries();
+            let result = id0.function_containing(u64::from(start) + 1);
+            #[cfg(feature = "restrictive")]
+            assert!(result.is_err());
+            #[cfg(not(feature = "restrictive"))]
+            assert!(result.unwrap().is_none());
+        }
+    }
+
+    #[test]
+    fn address_above_u32_in_32_bits_database() {
+        let file = BufReader::new(
+            File::open("resources/idbs/FlawedGrace.idb").unwrap(),
+        );
+        let mut parser = IDBParser::new(file).unwrap();
+        let id0 = parser
+            .read_id0_section(parser.id0_section_offset().unwrap())
+            .unwrap();
+        let address = 0x1_0000_0000u64;
+        assert!(id0.label_at(address).unwrap().is_none());
+        assert_eq!(id0.address_info_at(address).unwrap().count(), 0);
+        assert!(id0.function_containing(address).unwrap().is_none());
+        assert!(id0.sup_value_usize(address, b'S', 0).is_err());
+        assert!(id0.sup_value_u32(0, b'S', address).is_err());
+    }
+
+    #[test]
+    fn ida_info_unknown_flag_bits() {
+        let file =
+            BufReader::new(File::open("resources/idbs/gcc.i64").unwrap());
+        let mut parser = IDBParser::new(file).unwrap();
+        let mut id0 = parser
+            .read_id0_section(parser.id0_section_offset().unwrap())
+            .unwrap();
+        let mut report = ParseReport::new();
+        id0.ida_info_with_report(&mut report).unwrap();
+        assert!(report.is_empty());
+
+        // set the bit 0x8000 of the INFFL, after the magic, version and cpu
+        let root = id0.netnode_idx_by_name("Root Node").unwrap().unwrap();
+        let key = [
+            &b"."[..],
+            &root.to_be_bytes(),
+            b"S",
+            &0x41b994u64.to_be_bytes(),
+        ]
+        .concat();
+        let entry = id0.entries.iter_mut().find(|e| e.key == key).unwrap();
+        let start = 6 + usize::from(entry.value[5]);
+        let mut genflags_data = &entry.value[start..];
+        let genflags = packing::unpack_dw(&mut genflags_data).unwrap();
+        let end = entry.value.len() - genflags_data.len();
+        let genflags = genflags | 0x8000;
+        entry
+            .value
+            .splice(start..end, [0xC0, (genflags >> 8) as u8, genflags as u8]);
+
+        let mut report = ParseReport::new();
+        let result = id0.ida_info_with_report(&mut report);
+        #[cfg(feature = "restrictive")]
+        assert!(result.is_err());
+        #[cfg(not(feature = "restrictive"))]
+        {
+            let id0::IDBParam::V2(param) = result.unwrap() else {
+                unreachable!();
+            };
+            assert_eq!(param.genflags.unknown_bits(), 0x8000);
+            assert!(matches!(
+                report.warnings[..],
+                [Warning::UnknownFlagBits {
+                    field: "INFFL",
+                    bits: 0x8000
+                }]
+            ));
+        }
+    }
+
+    #[test]
+    fn ida_info_processor() {
+        for filename in
+            ["resources/idbs/gcc.i64", "resources/idbs/FlawedGrace.idb"]
+        {
+            let file = BufReader::new(File::open(filename).unwrap());
+            let mut parser = IDBParser::new(file).unwrap();
+            let id0 = parser
+                .read_id0_section(parser.id0_section_offset().unwrap())
+                .unwrap();
+            let ida_info = id0.ida_info().unwrap();
+            assert_eq!(ida_info.processor(), id0::Processor::X86);
+            assert_eq!(ida_info.processor_name(), "metapc");
+        }
+        assert_eq!(id0::Processor::from_name(b"ARMB"), id0::Processor::Arm);
+        assert_eq!(
+            id0::Processor::from_name(b"Z80"),
+            id0::Processor::Other(b"Z80".to_vec())
+        );
+    }
+
+    #[test]
+    fn database_upgrade_flags() {
+        use id0::ID0Section;
+        for (filename, flags) in [
+            ("resources/idbs/gcc.i64", 0x3eff_ffff),
+            ("resources/idbs/FlawedGrace.idb", 0x1aff_ffff),
+            ("resources/idbs/func_comment.idb", 0xf_ffff),
+        ] {
+            let file = BufReader::new(File::open(filename).unwrap());
+            let mut parser = IDBParser::new(file).unwrap();
+            let id0 = parser
+                .read_id0_section(parser.id0_section_offset().unwrap())
+                .unwrap();
+            let upgrade_flags = id0.database_upgrade_flags().unwrap().unwrap();
+            assert_eq!(upgrade_flags, flags);
+            assert_ne!(upgrade_flags & ID0Section::UPGRADE_700_0X40, 0);
+            assert_ne!(upgrade_flags & ID0Section::UPGRADE_700_0X10000, 0);
+        }
+    }
+
+    #[test]
+    fn root_node_strings() {
+        let id0 = |filename| {
+            let file = BufReader::new(File::open(filename).unwrap());
+            let mut parser = IDBParser::new(file).unwrap();
+            parser
+                .read_id0_section(parser.id0_section_offset().unwrap())
+                .unwrap()
+        };
+        let encodings = |id0: &ID0Section| -> Vec<String> {
+            id0.strings_encodings()
+                .unwrap()
+                .unwrap()
+                .iter()
+                .map(IDBString::to_string_lossy)
+                .collect()
+        };
+
+        let gcc = id0("resources/idbs/gcc.i64");
+        assert_eq!(gcc.abi_name().unwrap().unwrap().as_bytes(), b"osx");
+        assert!(gcc.archive_file_path().unwrap().is_none());
+        assert!(gcc.notepad().unwrap().is_none());
+        assert_eq!(encodings(&gcc), ["UTF-8", "UTF-16LE", "UTF-32LE"]);
+
+        let grace = id0("resources/idbs/FlawedGrace.idb");
+        assert!(grace.abi_name().unwrap().is_none());
+        let notepad = grace.notepad().unwrap().unwrap().to_string_lossy();
+        assert!(notepad.starts_with("How does the RDP tunneling"));
+        assert!(notepad.ends_with("puts the message into a queue."));
+        assert_eq!(
+            encodings(&grace),
+            ["UTF-8", "UTF-16LE", "windows-1252", "UTF-32LE"]
+        );
+    }
+
+    #[test]
+    fn loader_names_and_input_file_path() {
+        for (filename, loader_names, input_file_path) in [
+            (
+                "resources/idbs/gcc.i64",
+                &["macho64.dll", "Mach-O file (EXECUTE). X86_64"][..],
+                "C:\\Users\\zain\\Desktop\\complicated-gcc",
+            ),
+            (
+                "resources/idbs/madame.i64",
+                &["elf64.dll", "ELF64 for x86-64 (Executable)"],
+                "/mnt/c/Users/eliasb/OneDrive/SAMPLES/madam2_ctf/madame",
+            ),
+            (
+                "resources/idbs/func_comment.idb",
+                &[],
+                "Z:\\home\\user\\Documents\\code\\python-idb\\tests\\data\\small\\small.bin",
+            ),
+        ] {
+            let file = BufReader::new(File::open(filename).unwrap());
+            let mut parser = IDBParser::new(file).unwrap();
+            let id0 = parser
+                .read_id0_section(parser.id0_section_offset().unwrap())
+                .unwrap();
+            assert_eq!(id0.loader_names().unwrap(), loader_names);
+            assert_eq!(
+                id0.input_file_path().unwrap().as_deref(),
+                Some(input_file_path)
+            );
+        }
+    }
+
+    #[test]
+    fn parse_lflg_unknown_0x2000() {
+        // lflags from kernel32.i64
+        let lflg = id0::Lflg::new(0x2007).unwrap();
+        assert!(lflg.is_unknown_0x2000());
+        assert!(lflg.is_program_64b());
+        assert!(!lflg.is_ilp32());
+    }
+
     #[test]
     fn parse_idbs() {
         let files = find_all(
@@ -828,47 +1936,662 @@ mod test {
         }
     }
 
+    #[test]
+    fn fixups_resolve() {
+        use id0::FixupType;
+        for (filename, fixup_type, first) in [
+            ("resources/idbs/FlawedGrace.idb", FixupType::Off32, 0x401007),
+            ("resources/idbs/gcc.i64", FixupType::Off64, 0x1_0000_4000),
+        ] {
+            let file = BufReader::new(File::open(filename).unwrap());
+            let mut parser = IDBParser::new(file).unwrap();
+            let id0 = parser
+                .read_id0_section(parser.id0_section_offset().unwrap())
+                .unwrap();
+            let id1 = parser
+                .read_id1_section(parser.id1_section_offset().unwrap())
+                .unwrap();
+            let fixups: Vec<_> =
+                id0.fixups().unwrap().map(Result::unwrap).collect();
+            assert_eq!(fixups[0].address, first);
+            for fixup in fixups {
+                assert_eq!(fixup.fixup_type, fixup_type);
+                assert_eq!(fixup.resolve(&id1), Some(fixup.offset));
+            }
+        }
+    }
+
+    #[test]
+    fn root_node_sup_values() {
+        for (filename, is_64) in [
+            ("resources/idbs/FlawedGrace.idb", false),
+            ("resources/idbs/gcc.i64", true),
+        ] {
+            let file = BufReader::new(File::open(filename).unwrap());
+            let mut parser = IDBParser::new(file).unwrap();
+            let id0 = parser
+                .read_id0_section(parser.id0_section_offset().unwrap())
+                .unwrap();
+            let root = id0.netnode_idx_by_name("Root Node").unwrap().unwrap();
+            let version = id0
+                .root_info()
+                .unwrap()
+                .find_map(|info| match info.unwrap() {
+                    id0::IDBRootInfo::Version(version) => Some(version),
+                    _ => None,
+                })
+                .unwrap();
+            // RIDX_ALT_VERSION
+            let idx = if is_64 { u64::MAX } else { u32::MAX.into() };
+            let value = id0.sup_value_usize(root, b'A', idx).unwrap();
+            assert_eq!(value, Some(version));
+            let value = id0.sup_value_u32(root, b'A', idx);
+            if is_64 {
+                assert!(value.is_err());
+            } else {
+                assert_eq!(value.unwrap(), Some(version as u32));
+                assert!(id0.sup_value_usize(root, b'A', u64::MAX).is_err());
+            }
+            assert_eq!(id0.sup_value_usize(root, b'A', 0x1234).unwrap(), None);
+        }
+    }
+
+    #[test]
+    fn database_creation_time() {
+        let file =
+            BufReader::new(File::open("resources/idbs/gcc.i64").unwrap());
+        let mut parser = IDBParser::new(file).unwrap();
+        let id0 = parser
+            .read_id0_section(parser.id0_section_offset().unwrap())
+            .unwrap();
+        let created = id0
+            .root_info()
+            .unwrap()
+            .find_map(|info| match info.unwrap() {
+                id0::IDBRootInfo::CreatedDate(created) => Some(created),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(id0.database_creation_time().unwrap(), Some(created));
+        assert_eq!(id0.database_secs_opens().unwrap(), Some(1007694));
+        #[cfg(feature = "time")]
+        {
+            let utc = id0.database_creation_time_utc().unwrap().unwrap();
+            assert_eq!(utc.unix_timestamp(), created as i64);
+            assert_eq!(utc.date().year(), 2022);
+            let open = id0.database_open_duration().unwrap().unwrap();
+            assert_eq!(open.whole_days(), 11);
+        }
+    }
+
+    #[test]
+    fn parse_idb_from_memory() {
+        let data = std::fs::read("resources/idbs/gcc.i64").unwrap();
+        let mut parser = IDBParser::new(SliceReader::new(&data[..])).unwrap();
+        let id0 = parser
+            .read_id0_section(parser.id0_section_offset().unwrap())
+            .unwrap();
+        let til = parser
+            .read_til_section(parser.til_section_offset().unwrap())
+            .unwrap();
+        let file =
+            BufReader::new(File::open("resources/idbs/gcc.i64").unwrap());
+        let mut parser = IDBParser::new(file).unwrap();
+        let id0_file = parser
+            .read_id0_section(parser.id0_section_offset().unwrap())
+            .unwrap();
+        assert_eq!(id0.all_entries().count(), id0_file.all_entries().count());
+        assert!(!til.types.is_empty());
+    }
+
+    #[test]
+    fn til_read_header_only() {
+        let data = std::fs::read("resources/tils/gcc.til").unwrap();
+        let til = TILSection::read(&mut &data[..], IDBSectionCompression::None)
+            .unwrap();
+        let compressed =
+            compress_section(&data, IDBSectionCompression::Zlib).unwrap();
+        for (input, compress) in [
+            (&data[..], IDBSectionCompression::None),
+            (&compressed[..], IDBSectionCompression::Zlib),
+        ] {
+            let header =
+                TILSection::read_header_only(&mut &input[..], compress)
+                    .unwrap();
+            assert_eq!(format!("{header:?}"), format!("{:?}", til.header));
+        }
+
+        let file =
+            BufReader::new(File::open("resources/idbs/gcc.i64").unwrap());
+        let mut parser = IDBParser::new(file).unwrap();
+        let til_offset = parser.til_section_offset().unwrap();
+        let header = parser.read_til_section_header(til_offset).unwrap();
+        let til = parser.read_til_section(til_offset).unwrap();
+        assert_eq!(format!("{header:?}"), format!("{:?}", til.header));
+    }
+
+    #[test]
+    fn detect_section_compression() {
+        let data = std::fs::read("resources/tils/gcc.til").unwrap();
+        assert!(matches!(
+            detect_compression(&data),
+            Ok(IDBSectionCompression::None)
+        ));
+        let compressed =
+            compress_section(&data, IDBSectionCompression::Zlib).unwrap();
+        assert!(matches!(
+            detect_compression(&compressed),
+            Ok(IDBSectionCompression::Zlib)
+        ));
+        let til = TILSection::read(
+            &mut &compressed[..],
+            detect_compression(&compressed).unwrap(),
+        )
+        .unwrap();
+        let expected =
+            TILSection::read(&mut &data[..], IDBSectionCompression::None)
+                .unwrap();
+        assert_eq!(til.types.len(), expected.types.len());
+        assert!(detect_compression(&[0x28, 0xB5, 0x2F, 0xFD, 0]).is_err());
+    }
+
+    #[test]
+    fn compress_section_round_trip() {
+        let raw = std::fs::read("resources/idbs/gcc.i64").unwrap();
+        let mut parser = IDBParser::new(std::io::Cursor::new(&raw)).unwrap();
+        let id0_offset = parser.id0_section_offset().unwrap();
+        let expected = parser.read_id0_section(id0_offset).unwrap();
+        let mut data = vec![];
+        parser.decompress_section(id0_offset, &mut data).unwrap();
+        for compress in
+            [IDBSectionCompression::None, IDBSectionCompression::Zlib]
+        {
+            let compressed = compress_section(&data, compress).unwrap();
+            let mut section = vec![compress as u8];
+            match parser.header.version {
+                IDBVersion::V1 | IDBVersion::V4 => section.extend(
+                    u32::try_from(compressed.len()).unwrap().to_le_bytes(),
+                ),
+                IDBVersion::V5 | IDBVersion::V6 => section.extend(
+                    u64::try_from(compressed.len()).unwrap().to_le_bytes(),
+                ),
+            }
+            section.extend(&compressed);
+            // the section is uncompressed in the file, so it always fits
+            let offset = usize::try_from(id0_offset.idb_offset()).unwrap();
+            let mut file = raw.clone();
+            file[offset..offset + section.len()].copy_from_slice(&section);
+
+            let mut parser =
+                IDBParser::new(std::io::Cursor::new(file)).unwrap();
+            let id0_offset = parser.id0_section_offset().unwrap();
+            let id0 = parser.read_id0_section(id0_offset).unwrap();
+            assert_eq!(
+                id0.all_entries().count(),
+                expected.all_entries().count()
+            );
+            assert!(id0
+                .all_entries()
+                .zip(expected.all_entries())
+                .all(|(entry, expected)| entry.key == expected.key
+                    && entry.value == expected.value));
+            let mut result = vec![];
+            parser.decompress_section(id0_offset, &mut result).unwrap();
+            assert_eq!(result, data);
+        }
+    }
+
+    #[test]
+    #[cfg(not(feature = "restrictive"))]
+    fn id0_skip_corrupt_page() {
+        let file =
+            BufReader::new(File::open("resources/idbs/gcc.i64").unwrap());
+        let mut parser = IDBParser::new(file).unwrap();
+        let mut data = vec![];
+        parser
+            .decompress_section(parser.id0_section_offset().unwrap(), &mut data)
+            .unwrap();
+        let full = ID0Section::read(
+            &mut &data[..],
+            &parser.header,
+            IDBSectionCompression::None,
+        )
+        .unwrap();
+        assert!(!full.is_partial());
+
+        // corrupt the first child of the root page
+        let page_size = u16::from_le_bytes(data[4..6].try_into().unwrap());
+        let root = u32::from_le_bytes(data[6..10].try_into().unwrap());
+        let root_offset = root as usize * page_size as usize;
+        let child = u32::from_le_bytes(
+            data[root_offset..root_offset + 4].try_into().unwrap(),
+        );
+        assert_ne!(child, 0);
+        let child_offset = child as usize * page_size as usize;
+        data[child_offset..child_offset + page_size as usize].fill(0xFF);
+
+        let partial = ID0Section::read(
+            &mut &data[..],
+            &parser.header,
+            IDBSectionCompression::None,
+        )
+        .unwrap();
+        assert!(partial.is_partial());
+        assert_eq!(partial.skipped_pages()[0].page, child);
+        assert!(partial.all_entries().count() < full.all_entries().count());
+        assert!(partial
+            .all_entries()
+            .all(|entry| full.binary_search(&entry.key).is_ok()));
+    }
+
+    #[test]
+    #[cfg(not(feature = "restrictive"))]
+    fn id0_sort_unordered_entries() {
+        let file =
+            BufReader::new(File::open("resources/idbs/gcc.i64").unwrap());
+        let mut parser = IDBParser::new(file).unwrap();
+        let mut data = vec![];
+        parser
+            .decompress_section(parser.id0_section_offset().unwrap(), &mut data)
+            .unwrap();
+        let full = ID0Section::read(
+            &mut &data[..],
+            &parser.header,
+            IDBSectionCompression::None,
+        )
+        .unwrap();
+        assert!(!full.is_reordered());
+
+        // swap the first two children of the root page
+        let page_size =
+            usize::from(u16::from_le_bytes(data[4..6].try_into().unwrap()));
+        let root = u32::from_le_bytes(data[6..10].try_into().unwrap());
+        let root_offset = root as usize * page_size;
+        let child_offset = |offset: usize| {
+            let start = root_offset + offset;
+            let child =
+                u32::from_le_bytes(data[start..start + 4].try_into().unwrap());
+            child as usize * page_size
+        };
+        let (first, second) = (child_offset(0), child_offset(6));
+        let first_page = data[first..first + page_size].to_vec();
+        data.copy_within(second..second + page_size, first);
+        data[second..second + page_size].copy_from_slice(&first_page);
+
+        let reordered = ID0Section::read(
+            &mut &data[..],
+            &parser.header,
+            IDBSectionCompression::None,
+        )
+        .unwrap();
+        assert!(reordered.is_reordered());
+        assert_eq!(reordered.all_entries().count(), full.all_entries().count());
+        assert!(full
+            .all_entries()
+            .all(|entry| reordered.get(&entry.key).is_some()));
+
+        // manually unordered entries can also be fixed
+        let mut id0 = full.clone();
+        id0.entries.reverse();
+        id0.entries.push(id0.entries[0].clone());
+        id0.sort_entries();
+        assert_eq!(id0.all_entries().count(), full.all_entries().count());
+        assert!(full
+            .all_entries()
+            .all(|entry| id0.get(&entry.key).is_some()));
+    }
+
+    #[test]
+    fn compiler_raw_values() {
+        use id0::Compiler;
+        let known = [
+            (0x0, Compiler::Unknown),
+            (0x1, Compiler::VisualStudio),
+            (0x2, Compiler::Borland),
+            (0x3, Compiler::Watcom),
+            (0x6, Compiler::Gnu),
+            (0x7, Compiler::VisualAge),
+            (0x8, Compiler::Delphi),
+        ];
+        for (value, compiler) in known {
+            assert_eq!(Compiler::from_value(value), compiler);
+            assert_ne!(compiler.name(), "?");
+        }
+        for value in 0..=til::flag::comp::COMP_MASK {
+            assert_eq!(Compiler::from_value(value).into_raw(), value);
+        }
+        assert_eq!(Compiler::from_value(0x4), Compiler::Other(0x4));
+        // COMP_UNSURE is a flag on top of the compiler id
+        assert_eq!(Compiler::from_raw(0x01), (Compiler::VisualStudio, false));
+        assert_eq!(Compiler::from_raw(0x81), (Compiler::VisualStudio, true));
+        assert_eq!(Compiler::from_raw(0x86), (Compiler::Gnu, true));
+        assert_eq!(Compiler::from_raw(0x80), (Compiler::Unknown, true));
+        assert_eq!(Compiler::from_value(0x86), Compiler::Gnu);
+        assert_eq!(til::flag::comp::name(til::flag::comp::COMP_GNU), "GNU C++");
+        assert_eq!(til::flag::comp::name(0x4), "?");
+        assert_eq!(til::flag::comp::name(0x81), "Visual C++");
+    }
+
+    #[test]
+    fn idb_string_idc_literal() {
+        let value = IDBString::new(b"a\"b\\c\n\x01\xC3\xA9".to_vec());
+        assert_eq!(value.to_idc_literal(), r#""a\"b\\c\n\001\303\251""#);
+        use std::io::Write;
+        let mut built = IDBString::new(vec![]);
+        write!(built, "sub_{:X}", 0x401000).unwrap();
+        assert_eq!(built, "sub_401000");
+        assert_eq!(built.to_idc_literal(), "\"sub_401000\"");
+    }
+
+    #[test]
+    fn enum_bitmask_groups() {
+        use til::r#enum::{Enum, EnumFormat};
+        let name = |name: &str| Some(IDBString::new(name.as_bytes().to_vec()));
+        let til_enum = Enum {
+            is_signed: false,
+            is_unsigned: true,
+            output_format: EnumFormat::Hex,
+            members: vec![
+                (name("READ"), 0x1),
+                (name("MODE"), 0x6),
+                (name("MODE_A"), 0x2),
+                (name("MODE_B"), 0x4),
+            ],
+            groups: Some(vec![1, 3]),
+            storage_size: None,
+            attributes: Default::default(),
+        };
+        assert!(til_enum.is_bitmask());
+        let masks: Vec<_> = (0..til_enum.members.len())
+            .map(|idx| til_enum.member_mask(idx))
+            .collect();
+        assert_eq!(masks, [Some(0x1), Some(0x6), Some(0x6), Some(0x6)]);
+        assert_eq!(til_enum.member_mask(4), None);
+        let groups: Vec<_> = til_enum.bitmask_groups().collect();
+        assert_eq!(groups.len(), 2);
+        assert!(groups[0].is_single_bit());
+        assert_eq!(groups[1].mask, 0x6);
+        assert_eq!(groups[1].mask_name.unwrap().as_bytes(), b"MODE");
+        assert_eq!(groups[1].members, &til_enum.members[2..]);
+        let names: Vec<_> = til_enum
+            .names_for_value(0x5)
+            .into_iter()
+            .map(IDBString::as_bytes)
+            .collect();
+        assert_eq!(names, [&b"READ"[..], b"MODE_B"]);
+
+        let plain = Enum {
+            groups: None,
+            ..til_enum
+        };
+        assert!(!plain.is_bitmask());
+        assert_eq!(plain.member_mask(0), None);
+        assert_eq!(plain.bitmask_groups().count(), 0);
+    }
+
+    #[test]
+    fn bitfield_extract_sign_extension() {
+        use til::bitfield::Bitfield;
+        let field = |width, unsigned| Bitfield {
+            unsigned,
+            width,
+            nbytes: 8.try_into().unwrap(),
+        };
+        assert_eq!(field(1, false).extract_signed(0b10, 1), -1);
+        assert_eq!(field(1, true).extract_signed(0b10, 1), 1);
+        assert_eq!(field(4, false).extract_signed(0xf0, 4), -1);
+        assert_eq!(field(4, false).extract_signed(0x70, 4), 7);
+        assert_eq!(field(4, false).extract_signed(0x80, 4), -8);
+        assert_eq!(field(4, true).extract(0xf0, 4), 0xf);
+        assert_eq!(field(13, false).extract_signed(0x1000 << 3, 3), -0x1000);
+        assert_eq!(field(32, false).extract_signed(0xffff_fffe, 0), -2);
+        assert_eq!(field(63, false).extract_signed(u64::MAX << 1, 1), -1);
+        assert_eq!(field(64, false).extract_signed(u64::MAX, 0), -1);
+        assert_eq!(field(64, true).mask(), u64::MAX);
+        assert_eq!(field(0, false).extract_signed(u64::MAX, 0), 0);
+        assert_eq!(field(4, true).insert(0xffff, 4, 0x12), 0xff2f);
+        assert_eq!(field(4, true).insert(0, 60, 0xf), 0xf << 60);
+    }
+
+    #[test]
+    fn struct_member_offset_att() {
+        use til::r#struct::ExtAttBasicOffset;
+        let til = TILSection::read_from_idb(
+            File::open("resources/idbs/mdmebg-solved.i64").unwrap(),
+        )
+        .unwrap()
+        .unwrap();
+        let ty = til.get_name(b"Elf64_Sym").unwrap();
+        let til::TypeVariant::Struct(ty) = &ty.tinfo.type_variant else {
+            panic!("Elf64_Sym is not a struct");
+        };
+        let member = ty
+            .members
+            .iter()
+            .find(|member| {
+                member.name.as_ref().unwrap().as_bytes() == b"st_value"
+            })
+            .unwrap();
+        let att = member.att.unwrap();
+        assert_eq!(
+            att.basic_offset_type(),
+            Some(ExtAttBasicOffset {
+                offset: 0x9,
+                is_auto: false
+            })
+        );
+        assert!(att.str_type().is_none());
+        assert!(att.basic().is_none());
+    }
+
+    #[test]
+    fn sclass_raw_values() {
+        use til::SClass;
+        for value in 0..=u8::MAX {
+            assert_eq!(SClass::from_raw(value).into_raw(), value);
+        }
+        for (value, sclass) in SClass::KNOWN.into_iter().enumerate() {
+            assert_eq!(usize::from(sclass.into_raw()), value);
+        }
+        assert_eq!(SClass::from_raw(2), SClass::Extern);
+        assert_eq!(SClass::from_raw(8), SClass::Other(8));
+    }
+
+    #[test]
+    fn str_type_raw_values() {
+        use id0::{StrLayout, StrType, StrWidth};
+        assert_eq!(StrType::from_raw(0), Some(StrType::C));
+        // STRTYPE_C_16
+        let utf16 = StrType::from_raw(1).unwrap();
+        assert_eq!(utf16.width, StrWidth::Byte2);
+        assert_eq!(utf16.layout, StrLayout::Terminated);
+        // STRTYPE_LEN4_16
+        let len4 = StrType::from_raw(13).unwrap();
+        assert_eq!(
+            (len4.width, len4.layout.prefix_len()),
+            (StrWidth::Byte2, 4)
+        );
+        // STRTYPE_PASCAL with an encoding
+        let pascal = StrType::from_raw(0x0300_0004).unwrap();
+        assert_eq!(pascal.layout, StrLayout::Prefix1);
+        assert_eq!(pascal.encoding_idx, 3);
+        assert!(StrType::from_raw(3).is_none());
+        assert!(StrType::from_raw(0x10).is_none());
+        for value in [0, 1, 2, 4, 5, 8, 9, 12, 13, 0x0A0D_0002, 0x0300_0004] {
+            assert_eq!(StrType::from_raw(value).unwrap().into_raw(), value);
+        }
+    }
+
+    #[test]
+    fn align_mem_round_up() {
+        use til::align_mem;
+        assert_eq!(align_mem(5, 0), 5);
+        assert_eq!(align_mem(5, 1), 5);
+        assert_eq!(align_mem(0, 4), 0);
+        assert_eq!(align_mem(5, 4), 8);
+        assert_eq!(align_mem(8, 4), 8);
+        assert_eq!(align_mem(4, 3), 6);
+        assert_eq!(align_mem(6, 3), 6);
+        assert_eq!(align_mem(11, 10), 20);
+    }
+
     fn parse_idb(filename: impl AsRef<Path>) {
         let filename = filename.as_ref();
         println!("{}", filename.to_str().unwrap());
-        let file = BufReader::new(File::open(&filename).unwrap());
+        // the parser rewinds the input, and stops right after the header
+        let mut file = BufReader::new(File::open(filename).unwrap());
+        file.seek(SeekFrom::End(0)).unwrap();
+        let parser = IDBParser::new(file).unwrap();
+        let id0_offset = parser.id0_section_offset().unwrap().idb_offset();
+        let header_end = parser.into_inner().stream_position().unwrap();
+        assert!(header_end > 0 && header_end <= id0_offset);
+
+        let file = BufReader::new(File::open(filename).unwrap());
         let mut parser = IDBParser::new(file).unwrap();
+        let manifest = parser.section_manifest().unwrap();
+        assert!(manifest.id0.is_some());
+        assert_eq!(
+            manifest.til.is_some(),
+            parser.til_section_offset().is_some()
+        );
+        assert_eq!(
+            parser.is_64(),
+            filename.extension() == Some(OsStr::new("i64"))
+        );
         // parse sectors
-        let id0 = parser
-            .read_id0_section(parser.id0_section_offset().unwrap())
+        let (id0, report) = parser
+            .read_id0_section_with_report(parser.id0_section_offset().unwrap())
             .unwrap();
-        let til = parser
-            .til_section_offset()
-            .map(|til| parser.read_til_section(til).unwrap());
-        let _ = parser
+        assert!(!id0.is_partial());
+        assert!(report.is_empty());
+        let til = parser.til_section_offset().map(|til| {
+            let (til, report) =
+                parser.read_til_section_with_report(til).unwrap();
+            assert!(report.is_empty());
+            til
+        });
+        let id1 = parser
             .id1_section_offset()
             .map(|idx| parser.read_id1_section(idx));
-        let _ = parser
+        let id2 = parser
+            .id2_section_offset()
+            .map(|idx| parser.read_id2_section(idx).unwrap());
+        let nam = parser
             .nam_section_offset()
             .map(|idx| parser.read_nam_section(idx));
+        let til_from_idb =
+            TILSection::read_from_idb(File::open(filename).unwrap()).unwrap();
+        assert_eq!(
+            til.as_ref().map(|til| til.types.len()),
+            til_from_idb.map(|til| til.types.len())
+        );
+        if let Some(til) = &til {
+            for ty in til.types.iter().chain(&til.symbols) {
+                let (data, fields) = ty.tinfo.serialize(til).unwrap();
+                let new_ty =
+                    til::Type::deserialize(til, &data, &fields).unwrap();
+                assert_eq!(new_ty, ty.tinfo);
+            }
+        }
 
         // parse all id0 information
         let _ida_info = id0.ida_info().unwrap();
-        let version = match _ida_info {
-            id0::IDBParam::V1(x) => x.version,
-            id0::IDBParam::V2(x) => x.version,
-        };
-
-        let _: Vec<_> = id0.segments().unwrap().map(Result::unwrap).collect();
+        let version = _ida_info.version();
+        assert!(_ida_info.str_type().is_some());
+        let image_base = id0.image_base().unwrap();
+        let root_image_base = id0
+            .root_info()
+            .unwrap()
+            .map(Result::unwrap)
+            .find_map(|info| match info {
+                id0::IDBRootInfo::ImageBase(base) => Some(base),
+                _ => None,
+            });
+        assert_eq!(image_base, root_image_base);
+        assert!(id0.input_file_size().unwrap().unwrap() > 0);
+        let root_info = id0.parsed_root_info().unwrap();
+        assert_eq!(root_info.image_base(), image_base);
+        assert_eq!(root_info.netdelta(), _ida_info.netdelta());
+        assert_eq!(root_info.ida_info().version(), version);
+
+        let segments: Vec<_> =
+            id0.segments().unwrap().map(Result::unwrap).collect();
+        for segment in &segments {
+            assert_eq!(segment.address_range(), segment.address);
+            assert_eq!(segment.can_read(), segment.perm.map(|x| x.can_read()));
+            assert!(matches!(segment.bitness.bits(), 16 | 32 | 64));
+            if let Some(name) = &segment.name {
+                assert_eq!(segment.display_name().as_bytes(), &name[..]);
+            }
+            assert!(!segment.class_name().as_bytes().is_empty());
+        }
         let _: Vec<_> =
             id0.loader_name().unwrap().map(Result::unwrap).collect();
         let _: Vec<_> = id0.root_info().unwrap().map(Result::unwrap).collect();
-        let _: Vec<_> = id0
+        let _ = id0.database_upgrade_flags().unwrap();
+        let patches = id0.patches_original_values().unwrap();
+        let patches_flags = id0.patches_flags().unwrap();
+        assert_eq!(patches.len(), patches_flags.len());
+        assert!(patches
+            .iter()
+            .zip(&patches_flags)
+            .all(|((addr, _), (flag_addr, _))| addr == flag_addr));
+        let special_nodes = id0.special_nodes().unwrap();
+        assert!(special_nodes.root_node.is_some());
+        let regions: Vec<_> = id0
             .file_regions(version)
             .unwrap()
             .map(Result::unwrap)
             .collect();
-        let _: Vec<_> = id0
+        let regions_auto: Vec<_> = id0
+            .file_regions_auto()
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(regions.len(), regions_auto.len());
+        let functions_and_comments: Vec<_> = id0
             .functions_and_comments()
             .unwrap()
             .map(Result::unwrap)
             .collect();
-        let _ = id0.entry_points().unwrap();
+        let functions: Vec<_> =
+            id0.functions().unwrap().map(Result::unwrap).collect();
+        assert_eq!(
+            functions.len(),
+            functions_and_comments
+                .iter()
+                .filter(|entry| {
+                    matches!(entry, id0::FunctionsAndComments::Function(_))
+                })
+                .count()
+        );
+        if let Some(function) = functions
+            .iter()
+            .find(|function| !function.flags().is_tail())
+        {
+            let chunks = id0.function_chunks(function.address.start).unwrap();
+            let _ = id0.local_labels(function).unwrap();
+            assert_eq!(chunks[0].address, function.address);
+        }
+        for function in &functions {
+            let owner = match &function.extra {
+                Some(id0::IDBFunctionExtra::Tail { owner, .. }) => *owner,
+                _ => function.address.start,
+            };
+            assert!(functions
+                .iter()
+                .any(|f| { !f.flags().is_tail() && f.address.start == owner }));
+            for address in [function.address.start, function.address.end - 1] {
+                let found = id0.function_containing(address).unwrap().unwrap();
+                assert_eq!(found.address.start, owner);
+            }
+        }
+        let mut type_cache = til::TypeCache::new();
+        let entry_points = id0.entry_points().unwrap();
+        assert_eq!(
+            id0.entry_points_with_cache(&mut type_cache).unwrap(),
+            entry_points
+        );
         let _ = id0.dirtree_bpts().unwrap();
         let _ = id0.dirtree_enums().unwrap();
         let _dirtree_names = id0.dirtree_names().unwrap();
@@ -876,38 +2599,152 @@ mod test {
             // NOTE it's know that some label are missing in some databases
             let _name = id0.label_at(*addr).unwrap();
         });
+        if let Some(Ok(nam)) = &nam {
+            for name in id0.names(nam) {
+                let (address, name) = name.unwrap();
+                let label = id0.label_at(address).unwrap();
+                assert_eq!(label, Some(name.as_bytes()));
+            }
+        }
         let _dirtree_tinfos = id0.dirtree_tinfos().unwrap();
-        if let Some(til) = til {
+        if let Some(til) = &til {
             _dirtree_tinfos.visit_leafs(|ord| {
                 let _til = til.get_ord(*ord).unwrap();
             });
         }
         let _ = id0.dirtree_imports().unwrap();
         let _ = id0.dirtree_structs().unwrap();
-        let _ = id0.dirtree_function_address().unwrap();
+        let dirtree_funcs = id0.dirtree_function_address().unwrap();
+        let mut leafs = 0;
+        dirtree_funcs.visit_leafs(|_| leafs += 1);
+        let paths: Vec<_> = dirtree_funcs.iter_paths().collect();
+        assert_eq!(paths.len(), leafs);
+        assert!(paths
+            .iter()
+            .all(|(path, _)| !path.starts_with('/') && !path.ends_with('/')));
         let _ = id0.dirtree_bookmarks_tiplace().unwrap();
         let _ = id0.dirtree_bookmarks_idaplace().unwrap();
         let _ = id0.dirtree_bookmarks_structplace().unwrap();
-        let _: Vec<_> = id0
+        let address_info: Vec<_> = id0
             .address_info(version)
             .unwrap()
             .collect::<Result<_>>()
             .unwrap();
+        if let Some(Ok(id1)) = &id1 {
+            for (range, data) in id1.loaded_image() {
+                if let Some(data) = data {
+                    assert_eq!(data.len() as u64, range.end - range.start);
+                }
+            }
+            for seg in id1.segments() {
+                let range = seg.address_range();
+                assert_eq!(
+                    id1.bytes_in_range(range.clone()).count() as u64,
+                    range.end - range.start
+                );
+                if !range.is_empty() {
+                    let data = id1.segment_data(range.start).unwrap();
+                    assert_eq!(data.len() as u64, range.end - range.start);
+                }
+            }
+            for seg in id1.segments_with_id0(&segments) {
+                assert_eq!(seg.bitness(), seg.segment.bitness);
+                for (addr, _info) in seg.bytes() {
+                    assert!(seg.address_range().contains(&addr));
+                }
+            }
+            let _: Vec<_> = id0
+                .code_address_info(version, id1)
+                .unwrap()
+                .collect::<Result<_>>()
+                .unwrap();
+            let _: Vec<_> = id0
+                .data_address_info(version, id1)
+                .unwrap()
+                .collect::<Result<_>>()
+                .unwrap();
+            for literal in id0.string_literals(id1).unwrap() {
+                let (addr, str_type, data) = literal.unwrap();
+                assert!(id1.byte_info(addr).unwrap().is_string_literal());
+                assert_eq!(data.len() % str_type.width.bytes(), 0);
+            }
+        }
+        if let (Some(Ok(id1)), Some(id2)) = (&id1, &id2) {
+            // the ID1 and ID2 never contain the same address
+            for range in &id2.ranges {
+                assert!(id1.bytes_in_range(range.clone()).next().is_none());
+                assert!(id2.byte_by_address(range.start).is_some());
+                assert!(id2.byte_by_address(range.end - 1).is_some());
+            }
+        }
+        for (addr, info) in address_info {
+            if let Some(til) = &til {
+                // NOTE some types are know to be missing in some databases
+                if let Some(Ok(ty)) = info.applied_type(til) {
+                    let applied = id0.applied_tinfo(addr, til).unwrap();
+                    assert_eq!(applied.as_ref(), Some(ty));
+                    let cached = id0
+                        .applied_tinfo_with_cache(addr, til, &mut type_cache)
+                        .unwrap();
+                    assert_eq!(cached, applied);
+                }
+            }
+            match info {
+                id0::AddressInfo::Comment(id0::Comments::Comment(cmt)) => {
+                    assert_eq!(id0.comment(addr).unwrap(), Some(cmt));
+                }
+                id0::AddressInfo::Comment(
+                    id0::Comments::RepeatableComment(cmt),
+                ) => {
+                    assert_eq!(
+                        id0.comment_repeatable(addr).unwrap(),
+                        Some(cmt)
+                    );
+                }
+                _ => {}
+            }
+        }
     }
 
     #[test]
     fn parse_tils() {
         let files =
             find_all("resources/tils".as_ref(), &["til".as_ref()]).unwrap();
-        let _results = files
+        files
             .into_iter()
-            .map(|file| {
+            .try_for_each(|file| {
                 println!("{}", file.to_str().unwrap());
                 // makes sure it don't read out-of-bounds
                 let mut input = BufReader::new(File::open(file)?);
                 // TODO make a SmartReader
-                TILSection::read(&mut input, IDBSectionCompression::None).and_then(|_til| {
-                    let current = input.seek(SeekFrom::Current(0))?;
+                TILSection::read(&mut input, IDBSectionCompression::None).and_then(|til| {
+                    for ty in til.types.iter().chain(&til.symbols) {
+                        let (data, fields) = ty.tinfo.serialize(&til)?;
+                        ensure!(til::Type::deserialize(&til, &data, &fields)? == ty.tinfo);
+                    }
+                    for ty in &til.types {
+                        if let til::TypeVariant::Struct(til_struct) = &ty.tinfo.type_variant {
+                            ensure!(ty.tinfo.descendants().count() >= til_struct.members.len());
+                        }
+                        let til::TypeVariant::Enum(til_enum) = &ty.tinfo.type_variant else {
+                            continue;
+                        };
+                        for (name, value) in &til_enum.members {
+                            if name.is_some() {
+                                ensure!(til_enum.name_for_value(*value).is_some());
+                            }
+                            let _ = til_enum.names_for_value(*value);
+                        }
+                    }
+                    let flags = til.header.flags;
+                    ensure!(flags.iter_set().any(|name| name == "macro_table_present") == flags.has_macro_table());
+                    if let Some(entry) = til.macros().last() {
+                        ensure!(til.macro_by_name(&entry.name).is_some());
+                    }
+                    let ordinals: Vec<_> = til.ordinals().map(|(ord, _)| ord).collect();
+                    ensure!(ordinals.windows(2).all(|x| x[0] <= x[1]));
+                    ensure!(ordinals.iter().all(|ord| til.get_by_ordinal(*ord).is_some()));
+                    let current = input.stream_position()?;
                     let end = input.seek(SeekFrom::End(0))?;
                     ensure!(
                         current == end,
@@ -916,10 +2753,57 @@ mod test {
                     Ok(())
                 })
             })
-            .collect::<Result<(), _>>()
             .unwrap();
     }
 
+    #[test]
+    fn read_til_random_bytes() {
+        let data = std::fs::read("resources/tils/gcc.til").unwrap();
+        let til = TILSection::try_read_from_slice(&data).unwrap();
+        let types: Vec<_> = til
+            .types
+            .iter()
+            .chain(&til.symbols)
+            .map(|ty| ty.tinfo.serialize(&til).unwrap())
+            .collect();
+        // xorshift, so the results are reproducible
+        let mut seed = 0x2545_f491_4f6c_dd1du64;
+        let mut rng = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed
+        };
+        for i in 0..2000 {
+            let mut mutated = data.clone();
+            for _ in 0..1 + rng() % 4 {
+                let pos = rng() as usize % mutated.len();
+                mutated[pos] = rng() as u8;
+            }
+            if i % 8 == 0 {
+                mutated.truncate(rng() as usize % mutated.len());
+            }
+            let _ = TILSection::try_read_from_slice(&mutated);
+
+            let (ty, fields) = &types[i % types.len()];
+            let mut ty = ty.clone();
+            for _ in 0..1 + rng() % 4 {
+                let pos = rng() as usize % (ty.len() + 1);
+                ty.insert(pos, [rng() as u8, 0x80, 0xF1, 0xFE, 0xFF][i % 5]);
+            }
+            let _ = til::Type::deserialize(&til, &ty, fields);
+
+            let random: Vec<u8> =
+                (0..rng() % 64).map(|_| rng() as u8).collect();
+            let _ = TILSection::try_read_from_slice(&random);
+            let _ = til::Type::deserialize(&til, &random, &[]);
+        }
+        // deeply nested types are rejected, not a stack overflow
+        let mut nested = vec![til::flag::tf_ptr::BT_PTR; 0x10000];
+        nested.push(til::flag::tf_unk::BT_VOID);
+        assert!(til::Type::deserialize(&til, &nested, &[]).is_err());
+    }
+
     fn find_all(path: &Path, exts: &[&OsStr]) -> Result<Vec<PathBuf>> {
         fn inner_find_all(
             path: &Path,
@@ -0,0 +1,51 @@
+//! IDA packed integers, a variable-length encoding used on the database.
+//!
+//! The first byte of the value defines the number of bytes used, the
+//! remaining bytes are big-endian:
+//!
+//! - `dw`, up to 16 bits:
+//!   - `0xxx_xxxx`: 7 bits value
+//!   - `10xx_xxxx xxxx_xxxx`: 14 bits value
+//!   - `11__ ____ xxxx_xxxx xxxx_xxxx`: 16 bits value
+//! - `dd`, up to 32 bits:
+//!   - `0xxx_xxxx`: 7 bits value
+//!   - `10xx_xxxx xxxx_xxxx`: 14 bits value
+//!   - `110x_xxxx xxxx_xxxx xxxx_xxxx xxxx_xxxx`: 29 bits value
+//!   - `111_ ____` followed by 4 bytes: 32 bits value
+//! - `dq`, up to 64 bits: two `dd`, the low half first
+//! - `usize`: a `dq` for 64 bits databases, a `dd` otherwise
+
+use std::io::Read;
+use std::ops::Range;
+
+use anyhow::Result;
+
+use crate::ida_reader::{IdaGenericUnpack, IdaUnpack, IdaUnpacker};
+
+/// read a packed 16 bits value
+pub fn unpack_dw(input: &mut impl Read) -> Result<u16> {
+    input.unpack_dw()
+}
+
+/// read a packed 32 bits value
+pub fn unpack_dd(input: &mut impl Read) -> Result<u32> {
+    input.unpack_dd()
+}
+
+/// read a packed 64 bits value
+pub fn unpack_dq(input: &mut impl Read) -> Result<u64> {
+    input.unpack_dq()
+}
+
+/// read a packed address sized value
+pub fn unpack_usize(input: &mut impl Read, is_64: bool) -> Result<u64> {
+    IdaUnpacker::new(input, is_64).unpack_usize()
+}
+
+/// read a packed address range, stored as the start and the len
+pub fn unpack_address_range(
+    input: &mut impl Read,
+    is_64: bool,
+) -> Result<Range<u64>> {
+    IdaUnpacker::new(input, is_64).unpack_address_range()
+}
@@ -11,15 +11,18 @@ pub mod union;
 
 mod size_calculator;
 
-use section::TILSectionHeader;
+use section::{TILSection, TILSectionHeader};
 pub use size_calculator::*;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::num::NonZeroU8;
 
 use anyhow::{anyhow, ensure, Context, Result};
 
-use crate::ida_reader::{IdaGenericBufUnpack, IdaGenericUnpack};
+use crate::id0::Id0TilOrd;
+use crate::ida_reader::{
+    split_strings_from_array, IdaGenericBufUnpack, IdaGenericUnpack,
+};
 
 use crate::til::array::{Array, ArrayRaw};
 use crate::til::bitfield::Bitfield;
@@ -35,18 +38,34 @@ pub struct TILTypeInfo {
     pub name: IDBString,
     pub ordinal: u64,
     pub tinfo: Type,
+    pub sclass: SClass,
 }
 
 impl TILTypeInfo {
-    pub(crate) fn new(
+    /// create a type from scratch, eg: to add to a [TILSection]
+    pub fn new(name: IDBString, ordinal: u64, tinfo: Type) -> Self {
+        Self {
+            name,
+            ordinal,
+            tinfo,
+            sclass: SClass::Unknown,
+        }
+    }
+
+    pub(crate) fn from_raw(
         til: &TILSectionHeader,
         type_by_name: &HashMap<Vec<u8>, usize>,
         type_by_ord: &HashMap<u64, usize>,
-        name: IDBString,
-        ordinal: u64,
-        tinfo_raw: TypeRaw,
-        fields: Vec<Vec<u8>>,
+        raw: TILTypeInfoRaw,
     ) -> Result<Self> {
+        let TILTypeInfoRaw {
+            name,
+            ordinal,
+            tinfo: tinfo_raw,
+            fields,
+            sclass,
+            ..
+        } = raw;
         let mut fields_iter = fields
             .into_iter()
             .map(|field| (!field.is_empty()).then_some(IDBString::new(field)));
@@ -67,10 +86,83 @@ impl TILTypeInfo {
             name,
             ordinal,
             tinfo,
+            sclass: SClass::from_raw(sclass),
         })
     }
 }
 
+/// storage class of a symbol, AKA `sclass_t`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SClass {
+    Unknown,
+    Typedef,
+    Extern,
+    Static,
+    Register,
+    Auto,
+    Friend,
+    Virtual,
+    Other(u8),
+}
+
+impl SClass {
+    /// all the known storage classes
+    pub const KNOWN: [SClass; 8] = [
+        SClass::Unknown,
+        SClass::Typedef,
+        SClass::Extern,
+        SClass::Static,
+        SClass::Register,
+        SClass::Auto,
+        SClass::Friend,
+        SClass::Virtual,
+    ];
+
+    pub fn from_raw(value: u8) -> Self {
+        match value {
+            0 => Self::Unknown,
+            1 => Self::Typedef,
+            2 => Self::Extern,
+            3 => Self::Static,
+            4 => Self::Register,
+            5 => Self::Auto,
+            6 => Self::Friend,
+            7 => Self::Virtual,
+            other => Self::Other(other),
+        }
+    }
+
+    pub fn into_raw(self) -> u8 {
+        match self {
+            Self::Unknown => 0,
+            Self::Typedef => 1,
+            Self::Extern => 2,
+            Self::Static => 3,
+            Self::Register => 4,
+            Self::Auto => 5,
+            Self::Friend => 6,
+            Self::Virtual => 7,
+            Self::Other(value) => value,
+        }
+    }
+
+    /// the name of the storage class, as printed by IDA, empty for
+    /// [SClass::Unknown] and `?!` for invalid values
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Unknown => "",
+            Self::Typedef => "typedef",
+            Self::Extern => "extern",
+            Self::Static => "static",
+            Self::Register => "register",
+            Self::Auto => "auto",
+            Self::Friend => "friend",
+            Self::Virtual => "virtual",
+            Self::Other(_) => "?!",
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct TILTypeInfoRaw {
     _flags: u32,
@@ -80,7 +172,7 @@ pub(crate) struct TILTypeInfoRaw {
     _cmt: Vec<u8>,
     _fieldcmts: Vec<u8>,
     fields: Vec<Vec<u8>>,
-    _sclass: u8,
+    sclass: u8,
 }
 
 impl TILTypeInfoRaw {
@@ -139,19 +231,19 @@ impl TILTypeInfoRaw {
             _cmt: cmt,
             fields,
             _fieldcmts: fieldcmts,
-            _sclass: sclass,
+            sclass,
         })
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Type {
     pub is_const: bool,
     pub is_volatile: bool,
     pub type_variant: TypeVariant,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TypeVariant {
     Basic(Basic),
     Pointer(Pointer),
@@ -266,6 +358,318 @@ impl Type {
         );
         Ok(result)
     }
+
+    /// parse the type from the type and fields strings, the same format
+    /// produced by [Type::serialize], using the section to resolve the
+    /// typerefs
+    pub fn deserialize(
+        section: &TILSection,
+        data: &[u8],
+        fields: &[u8],
+    ) -> Result<Self> {
+        // the type is stored as a c-str, the parser may need to peek the
+        // nul terminator
+        let data: Vec<u8> = data
+            .strip_suffix(b"\x00")
+            .unwrap_or(data)
+            .iter()
+            .copied()
+            .chain([0])
+            .collect();
+        let mut reader = &data[..];
+        let type_raw = TypeRaw::read(&mut reader, &section.header)?;
+        #[cfg(feature = "restrictive")]
+        ensure!(
+            reader == b"\x00",
+            "Unable to parse type fully, left {} bytes",
+            reader.len()
+        );
+        let fields = split_strings_from_array(fields)
+            .ok_or_else(|| anyhow!("Invalid fields {fields:02x?}"))?;
+        let type_by_name = section
+            .types
+            .iter()
+            .enumerate()
+            .map(|(i, ty)| (ty.name.as_bytes().to_vec(), i))
+            .collect();
+        let type_by_ord = section
+            .types
+            .iter()
+            .enumerate()
+            .map(|(i, ty)| (ty.ordinal, i))
+            .collect();
+        let mut fields_iter = fields
+            .into_iter()
+            .map(|field| (!field.is_empty()).then_some(IDBString::new(field)));
+        let result = Self::new(
+            &section.header,
+            &type_by_name,
+            &type_by_ord,
+            type_raw,
+            &mut fields_iter,
+        )?;
+        #[cfg(feature = "restrictive")]
+        ensure!(fields_iter.next().is_none(), "Extra fields found for type");
+        Ok(result)
+    }
+
+    /// serialize the type into the type and fields strings, the same way
+    /// it's stored on the TIL section, the reverse of [Type::deserialize]
+    pub fn serialize(
+        &self,
+        section: &TILSection,
+    ) -> Result<(Vec<u8>, Vec<u8>)> {
+        let mut output = vec![];
+        let mut fields = vec![];
+        self.write(section, &mut output, &mut fields)?;
+        // missing fields at the end are the same as empty ones
+        while let Some(None) = fields.last() {
+            fields.pop();
+        }
+        let mut fields_output = vec![];
+        for field in fields {
+            let field = field.map(IDBString::as_bytes).unwrap_or(&[]);
+            let len = u8::try_from(field.len() + 1)
+                .ok()
+                .filter(|_| !field.contains(&0))
+                .ok_or_else(|| anyhow!("Invalid field {field:02x?}"))?;
+            fields_output.push(len);
+            fields_output.extend(field);
+        }
+        Ok((output, fields_output))
+    }
+
+    /// the type attributes not decoded by the type, only structs, unions
+    /// and enums keep them, see [StructMember::attributes] for the members
+    pub fn type_attributes(&self) -> Option<&TypeAttributes> {
+        match &self.type_variant {
+            TypeVariant::Struct(til_struct) => Some(&til_struct.attributes),
+            TypeVariant::Union(til_union) => Some(&til_union.attributes),
+            TypeVariant::Enum(til_enum) => Some(&til_enum.attributes),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn write<'a>(
+        &'a self,
+        section: &TILSection,
+        output: &mut Vec<u8>,
+        fields: &mut Vec<Option<&'a IDBString>>,
+    ) -> Result<()> {
+        let start = output.len();
+        match &self.type_variant {
+            TypeVariant::Basic(basic) => {
+                basic.write(&section.header, output)?
+            }
+            TypeVariant::Pointer(pointer) => {
+                pointer.write(section, output, fields)?
+            }
+            TypeVariant::Function(function) => {
+                function.write(section, output, fields)?
+            }
+            TypeVariant::Array(array) => {
+                array.write(section, output, fields)?
+            }
+            TypeVariant::Typeref(typeref) => typeref.write(section, output)?,
+            TypeVariant::Struct(til_struct) => {
+                til_struct.write(section, output, fields)?
+            }
+            TypeVariant::Union(til_union) => {
+                til_union.write(section, output, fields)?
+            }
+            TypeVariant::Enum(til_enum) => til_enum.write(output, fields)?,
+            TypeVariant::Bitfield(bitfield) => bitfield.write(output)?,
+        }
+        // the modifiers are part of the first byte
+        if self.is_const {
+            output[start] |= flag::tf_modifiers::BTM_CONST;
+        }
+        if self.is_volatile {
+            output[start] |= flag::tf_modifiers::BTM_VOLATILE;
+        }
+        Ok(())
+    }
+
+    /// iterate over all the types nested in this one, not following typerefs
+    pub fn descendants(&self) -> TypeDescendants<'_> {
+        let mut result = TypeDescendants { stack: vec![] };
+        result.push_children(self);
+        result
+    }
+
+    /// the pointer levels of this type, outermost first. Each one have it's
+    /// own closure, modifier (eg: `__ptr32`) and shifted parent
+    pub fn pointers(&self) -> impl Iterator<Item = &Pointer> {
+        fn as_pointer(ty: &Type) -> Option<&Pointer> {
+            match &ty.type_variant {
+                TypeVariant::Pointer(pointer) => Some(pointer),
+                _ => None,
+            }
+        }
+        std::iter::successors(as_pointer(self), |pointer| {
+            as_pointer(pointer.pointee())
+        })
+    }
+
+    /// number of pointer levels, eg: 2 for `char **`
+    pub fn pointer_depth(&self) -> usize {
+        self.pointers().count()
+    }
+
+    /// the type after removing all the pointer levels, and the number of
+    /// levels removed. Typerefs are not followed.
+    pub fn strip_pointers(&self) -> (&Type, usize) {
+        let mut ty = self;
+        let mut depth = 0;
+        while let TypeVariant::Pointer(pointer) = &ty.type_variant {
+            ty = pointer.pointee();
+            depth += 1;
+        }
+        (ty, depth)
+    }
+
+    /// check if all the typerefs in this type, including the ones in the
+    /// referenced types, can be found in the section
+    pub fn is_fully_resolved(&self, section: &TILSection) -> bool {
+        self.is_fully_resolved_inner(section, &mut HashSet::new())
+    }
+
+    fn is_fully_resolved_inner(
+        &self,
+        section: &TILSection,
+        visited: &mut HashSet<usize>,
+    ) -> bool {
+        match &self.type_variant {
+            TypeVariant::Basic(_)
+            | TypeVariant::Enum(_)
+            | TypeVariant::Bitfield(_) => true,
+            TypeVariant::Pointer(pointer) => {
+                pointer.typ.is_fully_resolved_inner(section, visited)
+                    && pointer.shifted.as_ref().is_none_or(|(ty, _)| {
+                        ty.is_fully_resolved_inner(section, visited)
+                    })
+            }
+            TypeVariant::Function(function) => {
+                function.ret.is_fully_resolved_inner(section, visited)
+                    && function.args.iter().all(|(_, ty, _)| {
+                        ty.is_fully_resolved_inner(section, visited)
+                    })
+            }
+            TypeVariant::Array(array) => {
+                array.elem_type.is_fully_resolved_inner(section, visited)
+            }
+            TypeVariant::Typeref(typeref) => {
+                let Some(idx) = typeref.resolve_idx(section) else {
+                    return false;
+                };
+                // already checked or being checked, avoid looping forever
+                if !visited.insert(idx) {
+                    return true;
+                }
+                section.types[idx]
+                    .tinfo
+                    .is_fully_resolved_inner(section, visited)
+            }
+            TypeVariant::Struct(til_struct) => {
+                til_struct.members.iter().all(|member| {
+                    member.member_type.is_fully_resolved_inner(section, visited)
+                })
+            }
+            TypeVariant::Union(til_union) => til_union
+                .members
+                .iter()
+                .all(|(_, ty)| ty.is_fully_resolved_inner(section, visited)),
+        }
+    }
+}
+
+/// cache of types decoded from the id0, keyed by the raw type and fields
+///
+/// Databases frequently share the same type blob between many entries, the
+/// cache allow those to be decoded only once.
+#[derive(Debug, Clone, Default)]
+pub struct TypeCache {
+    types: HashMap<(Vec<u8>, Vec<Vec<u8>>), Type>,
+}
+
+impl TypeCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// number of distinct types cached
+    pub fn len(&self) -> usize {
+        self.types.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.types.clear()
+    }
+
+    /// same as [Type::new_from_id0], but only decode each blob once
+    pub(crate) fn new_from_id0(
+        &mut self,
+        data: &[u8],
+        fields: Vec<Vec<u8>>,
+    ) -> Result<&Type> {
+        use std::collections::hash_map::Entry;
+        match self.types.entry((data.to_vec(), fields)) {
+            Entry::Occupied(entry) => Ok(entry.into_mut()),
+            Entry::Vacant(entry) => {
+                let ty = Type::new_from_id0(data, entry.key().1.clone())?;
+                Ok(entry.insert(ty))
+            }
+        }
+    }
+}
+
+/// Iterator over the nested types, created by [Type::descendants]
+pub struct TypeDescendants<'a> {
+    stack: Vec<&'a Type>,
+}
+
+impl<'a> TypeDescendants<'a> {
+    fn push_children(&mut self, ty: &'a Type) {
+        // pushed in reverse, so the types are returned in order
+        match &ty.type_variant {
+            TypeVariant::Basic(_)
+            | TypeVariant::Typeref(_)
+            | TypeVariant::Enum(_)
+            | TypeVariant::Bitfield(_) => {}
+            TypeVariant::Pointer(pointer) => {
+                if let Some((shifted, _)) = &pointer.shifted {
+                    self.stack.push(shifted);
+                }
+                self.stack.push(&pointer.typ);
+            }
+            TypeVariant::Function(function) => {
+                self.stack
+                    .extend(function.args.iter().rev().map(|(_, ty, _)| ty));
+                self.stack.push(&function.ret);
+            }
+            TypeVariant::Array(array) => self.stack.push(&array.elem_type),
+            TypeVariant::Struct(til_struct) => self.stack.extend(
+                til_struct.members.iter().rev().map(|m| &m.member_type),
+            ),
+            TypeVariant::Union(til_union) => self
+                .stack
+                .extend(til_union.members.iter().rev().map(|(_, ty)| ty)),
+        }
+    }
+}
+
+impl<'a> Iterator for TypeDescendants<'a> {
+    type Item = &'a Type;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let ty = self.stack.pop()?;
+        self.push_children(ty);
+        Some(ty)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -291,11 +695,24 @@ pub(crate) enum TypeVariantRaw {
     Bitfield(Bitfield),
 }
 
+/// limit for nested types, deeper types are rejected to avoid a stack overflow
+const MAX_TYPE_DEPTH: usize = 128;
+
 impl TypeRaw {
     pub fn read(
         input: &mut impl IdaGenericBufUnpack,
         til: &TILSectionHeader,
     ) -> Result<Self> {
+        Self::read_depth(input, til, 0)
+    }
+
+    /// `depth` is the number of types this one is nested in
+    pub(crate) fn read_depth(
+        input: &mut impl IdaGenericBufUnpack,
+        til: &TILSectionHeader,
+        depth: usize,
+    ) -> Result<Self> {
+        ensure!(depth < MAX_TYPE_DEPTH, "Type is nested too deeply");
         let metadata: u8 = input.read_u8()?;
         let type_base = metadata & flag::tf_mask::TYPE_BASE_MASK;
         let type_flags = metadata & flag::tf_mask::TYPE_FLAGS_MASK;
@@ -316,21 +733,21 @@ impl TypeRaw {
             }
             // InnerRef fb47f2c2-3c08-4d40-b7ab-3c7736dce31d 0x4804d7
             (flag::tf_ptr::BT_PTR, _) => {
-                PointerRaw::read(input, til, type_flags)
+                PointerRaw::read(input, til, type_flags, depth + 1)
                     .context("Type::Pointer")
                     .map(TypeVariantRaw::Pointer)?
             }
 
             // InnerRef fb47f2c2-3c08-4d40-b7ab-3c7736dce31d 0x48075a
             (flag::tf_array::BT_ARRAY, _) => {
-                ArrayRaw::read(input, til, type_flags)
+                ArrayRaw::read(input, til, type_flags, depth + 1)
                     .context("Type::Array")
                     .map(TypeVariantRaw::Array)?
             }
 
             // InnerRef fb47f2c2-3c08-4d40-b7ab-3c7736dce31d 0x48055d
             (flag::tf_func::BT_FUNC, _) => {
-                FunctionRaw::read(input, til, type_flags)
+                FunctionRaw::read(input, til, type_flags, depth + 1)
                     .context("Type::Function")
                     .map(TypeVariantRaw::Function)?
             }
@@ -351,18 +768,19 @@ impl TypeRaw {
             // InnerRef fb47f2c2-3c08-4d40-b7ab-3c7736dce31d 0x4803b4
             // InnerRef fb47f2c2-3c08-4d40-b7ab-3c7736dce31d 0x4808f9
             (flag::tf_complex::BT_COMPLEX, flag::tf_complex::BTMT_UNION) => {
-                UnionRaw::read(input, til).context("Type::Union")?
+                UnionRaw::read(input, til, depth + 1).context("Type::Union")?
             }
 
             // InnerRef fb47f2c2-3c08-4d40-b7ab-3c7736dce31d 0x4803b4
             // InnerRef fb47f2c2-3c08-4d40-b7ab-3c7736dce31d 0x4808f9
             (flag::tf_complex::BT_COMPLEX, flag::tf_complex::BTMT_STRUCT) => {
-                StructRaw::read(input, til).context("Type::Struct")?
+                StructRaw::read(input, til, depth + 1)
+                    .context("Type::Struct")?
             }
 
             // InnerRef fb47f2c2-3c08-4d40-b7ab-3c7736dce31d 0x4803b4
             (flag::tf_complex::BT_COMPLEX, flag::tf_complex::BTMT_ENUM) => {
-                EnumRaw::read(input, til).context("Type::Enum")?
+                EnumRaw::read(input, til, depth + 1).context("Type::Enum")?
             }
 
             (flag::tf_complex::BT_COMPLEX, _) => unreachable!(),
@@ -384,6 +802,7 @@ impl TypeRaw {
     pub fn read_ref(
         input: &mut impl IdaGenericUnpack,
         header: &TILSectionHeader,
+        depth: usize,
     ) -> Result<Self> {
         let mut bytes = input.unpack_dt_bytes()?;
 
@@ -393,14 +812,14 @@ impl TypeRaw {
         }
 
         let mut bytes = &bytes[..];
-        let result = TypeRaw::read(&mut bytes, header)?;
+        let result = TypeRaw::read_depth(&mut bytes, header, depth)?;
         #[cfg(feature = "restrictive")]
         ensure!(bytes.is_empty(), "Unable to fully parser Type ref");
         Ok(result)
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Basic {
     Void,
     // NOTE Unknown with 0 bytes is NOT the same as Void
@@ -542,6 +961,80 @@ impl Basic {
             _ => Err(anyhow!("Unknown Unset Type {}", btmt)),
         }
     }
+
+    fn write(
+        &self,
+        til: &TILSectionHeader,
+        output: &mut Vec<u8>,
+    ) -> Result<()> {
+        use flag::{tf_bool::*, tf_float::*, tf_int::*, tf_unk::*};
+        let sign = |is_signed| match is_signed {
+            None => BTMT_UNKSIGN,
+            Some(true) => BTMT_SIGNED,
+            Some(false) => BTMT_UNSIGNED,
+        };
+        let int_sized = |bytes: u8| match bytes {
+            1 => Ok(BT_INT8),
+            2 => Ok(BT_INT16),
+            4 => Ok(BT_INT32),
+            8 => Ok(BT_INT64),
+            16 => Ok(BT_INT128),
+            _ => Err(anyhow!("Invalid Int size {bytes}")),
+        };
+        let sizes = til.extended_sizeof_info.as_ref();
+        let value = match *self {
+            Self::Void => BT_VOID | BTMT_SIZE0,
+            Self::Unknown { bytes: 0 } => BT_UNK | BTMT_SIZE128,
+            Self::Unknown { bytes: 1 } => BT_VOID | BTMT_SIZE12,
+            Self::Unknown { bytes: 2 } => BT_UNK | BTMT_SIZE12,
+            Self::Unknown { bytes: 4 } => BT_VOID | BTMT_SIZE48,
+            Self::Unknown { bytes: 8 } => BT_UNK | BTMT_SIZE48,
+            Self::Unknown { bytes: 16 } => BT_VOID | BTMT_SIZE128,
+            Self::Unknown { bytes } => {
+                return Err(anyhow!("Invalid Unknown size {bytes}"))
+            }
+            Self::Bool => BT_BOOL | BTMT_DEFBOOL,
+            Self::BoolSized { bytes } => match bytes.get() {
+                1 => BT_BOOL | BTMT_BOOL1,
+                4 => BT_BOOL | BTMT_BOOL4,
+                // TODO same as the parser, BOOL8 is read as 2 bytes
+                2 => BT_BOOL | BTMT_BOOL8,
+                _ if bytes == til.size_bool => BT_BOOL | BTMT_DEFBOOL,
+                _ => return Err(anyhow!("Invalid Bool size {bytes}")),
+            },
+            Self::Char => BT_INT8 | BTMT_CHAR,
+            Self::SegReg => BT_INT | BTMT_CHAR,
+            Self::Short { is_signed } => {
+                let bytes = sizes.map(|x| x.size_short.get()).unwrap_or(2);
+                int_sized(bytes)? | sign(is_signed)
+            }
+            Self::Long { is_signed } => {
+                let bytes = sizes.map(|x| x.size_long.get()).unwrap_or(4);
+                int_sized(bytes)? | sign(is_signed)
+            }
+            Self::LongLong { is_signed } => {
+                let bytes = sizes.map(|x| x.size_long_long.get()).unwrap_or(8);
+                int_sized(bytes)? | sign(is_signed)
+            }
+            Self::Int { is_signed } => BT_INT | sign(is_signed),
+            Self::IntSized { bytes, is_signed } => {
+                int_sized(bytes.get())? | sign(is_signed)
+            }
+            Self::Float { bytes } => match bytes.get() {
+                4 => BT_FLOAT | BTMT_FLOAT,
+                8 => BT_FLOAT | BTMT_DOUBLE,
+                // TODO same as the parser, tbyte is read as 2 bytes
+                2 => BT_FLOAT | BTMT_SPECFLT,
+                _ if Some(bytes) == til.size_long_double => {
+                    BT_FLOAT | BTMT_LNGDBL
+                }
+                _ => return Err(anyhow!("Invalid Float size {bytes}")),
+            },
+            Self::LongDouble => BT_FLOAT | BTMT_LNGDBL,
+        };
+        output.push(value);
+        Ok(())
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -571,15 +1064,28 @@ impl TypedefRaw {
             })),
         }
     }
+
+    /// the value without the len prefix
+    fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            Self::Ordinal(ord) => {
+                let mut buf = vec![b'#'];
+                write_de(&mut buf, *ord);
+                buf
+            }
+            Self::Name(None) => vec![],
+            Self::Name(Some(name)) => name.as_bytes().to_vec(),
+        }
+    }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Typeref {
     pub ref_type: Option<TyperefType>,
     pub typeref_value: TyperefValue,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum TyperefValue {
     Ref(usize),
     UnsolvedName(Option<IDBString>),
@@ -632,6 +1138,71 @@ impl Typeref {
         })
     }
 
+    /// find the index of the referenced type in the section, if any
+    pub fn resolve_idx(&self, section: &TILSection) -> Option<usize> {
+        match &self.typeref_value {
+            TyperefValue::Ref(idx) => {
+                (*idx < section.types.len()).then_some(*idx)
+            }
+            TyperefValue::UnsolvedName(Some(name)) => {
+                section.get_name_idx(name.as_bytes())
+            }
+            TyperefValue::UnsolvedName(None) => None,
+            TyperefValue::UnsolvedOrd(ord) => {
+                section.get_ord_idx(Id0TilOrd { ord: (*ord).into() })
+            }
+        }
+    }
+
+    fn write(&self, section: &TILSection, output: &mut Vec<u8>) -> Result<()> {
+        use flag::tf_complex::*;
+        let raw = match &self.typeref_value {
+            TyperefValue::Ref(idx) => {
+                let ty = section.types.get(*idx).ok_or_else(|| {
+                    anyhow!("Typeref to invalid type index {idx}")
+                })?;
+                // the parser maps names and ordinals to the last type found
+                let name = ty.name.as_bytes();
+                let ord = u32::try_from(ty.ordinal).ok();
+                if !name.is_empty()
+                    && section
+                        .types
+                        .iter()
+                        .rposition(|x| x.name.as_bytes() == name)
+                        == Some(*idx)
+                {
+                    TypedefRaw::Name(Some(ty.name.clone()))
+                } else if let Some(ord) = ord.filter(|ord| {
+                    section
+                        .types
+                        .iter()
+                        .rposition(|x| x.ordinal == u64::from(*ord))
+                        == Some(*idx)
+                }) {
+                    TypedefRaw::Ordinal(ord)
+                } else {
+                    return Err(anyhow!(
+                        "Unable to reference the type index {idx}"
+                    ));
+                }
+            }
+            TyperefValue::UnsolvedName(name) => TypedefRaw::Name(name.clone()),
+            TyperefValue::UnsolvedOrd(ord) => TypedefRaw::Ordinal(*ord),
+        };
+        let btmt = match self.ref_type {
+            None => BTMT_TYPEDEF,
+            Some(TyperefType::Struct) => BTMT_STRUCT,
+            Some(TyperefType::Union) => BTMT_UNION,
+            Some(TyperefType::Enum) => BTMT_ENUM,
+        };
+        output.push(BT_COMPLEX | btmt);
+        if self.ref_type.is_some() {
+            // zero members means it's a reference
+            write_dt(output, 0)?;
+        }
+        write_dt_bytes(output, &raw.to_bytes())
+    }
+
     fn new_struct(
         type_by_name: &HashMap<Vec<u8>, usize>,
         type_by_ord: &HashMap<u64, usize>,
@@ -775,10 +1346,42 @@ pub struct TypeAttribute {
     pub extended: Option<Vec<TypeAttributeExt>>,
 }
 
-#[derive(Clone, Debug)]
+/// a raw extended type attribute, identified by the `key`
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct TypeAttributeExt {
-    pub _value1: Vec<u8>,
-    pub _value2: Vec<u8>,
+    pub key: Vec<u8>,
+    pub value: Vec<u8>,
+}
+
+/// the type attributes (TAH/TAUDT/TAFLD) not decoded by the type, kept raw
+/// so they can be inspected and written back
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TypeAttributes {
+    /// `tattr` bits unknown to the type
+    pub unknown_flags: u16,
+    /// extended attributes, in the order they are stored
+    pub extended: Vec<TypeAttributeExt>,
+}
+
+impl TypeAttributes {
+    pub(crate) fn from_raw(attribute: TypeAttribute, known: u16) -> Self {
+        Self {
+            unknown_flags: attribute.tattr & !known,
+            extended: attribute.extended.unwrap_or_default(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.unknown_flags == 0 && self.extended.is_empty()
+    }
+
+    /// the value of the extended attribute with this `key`
+    pub fn get(&self, key: &[u8]) -> Option<&[u8]> {
+        self.extended
+            .iter()
+            .find(|attr| attr.key == key)
+            .map(|attr| attr.value.as_slice())
+    }
 }
 
 fn serialize_dt(value: u16) -> Result<Vec<u8>> {
@@ -796,6 +1399,175 @@ fn serialize_dt(value: u16) -> Result<Vec<u8>> {
     Ok(result)
 }
 
+fn write_dt(output: &mut Vec<u8>, value: u16) -> Result<()> {
+    output.extend(serialize_dt(value)?);
+    Ok(())
+}
+
+/// the reverse of [IdaGenericUnpack::read_de]
+fn write_de(output: &mut Vec<u8>, value: u32) {
+    // the last byte holds the lower 6 bits, the others 7 bits each, the
+    // 0x40 bit is ignored by the reader but avoids a zero byte
+    let mut upper = vec![];
+    let mut rest = value >> 6;
+    while rest != 0 {
+        upper.push((rest & 0x7F) as u8 | 0x80);
+        rest >>= 7;
+    }
+    output.extend(upper.into_iter().rev());
+    output.push((value & 0x3F) as u8 | 0x40);
+}
+
+/// the reverse of [IdaGenericUnpack::read_dt_de]
+fn write_dt_de(output: &mut Vec<u8>, value: u32) -> Result<()> {
+    match value {
+        // zero is reserved for `None`
+        0 | 0x7FFE.. => {
+            write_dt(output, 0x7FFE)?;
+            write_de(output, value);
+            Ok(())
+        }
+        _ => write_dt(output, value as u16),
+    }
+}
+
+fn write_dt_bytes(output: &mut Vec<u8>, value: &[u8]) -> Result<()> {
+    let len = u16::try_from(value.len())
+        .map_err(|_| anyhow!("Value is too big for DT"))?;
+    write_dt(output, len)?;
+    output.extend(value);
+    Ok(())
+}
+
+/// the reverse of [IdaGenericUnpack::read_ext_att]
+fn write_ext_att(output: &mut Vec<u8>, value: u64) -> Result<()> {
+    if value == u64::MAX {
+        return write_dt(output, 0x400);
+    }
+    // only the non-zero bytes are written
+    let bytes = value.to_le_bytes();
+    let present = bytes
+        .iter()
+        .enumerate()
+        .filter(|(_i, byte)| **byte != 0)
+        .fold(0, |acc, (i, _byte)| acc | 1 << i);
+    write_dt(output, present)?;
+    output.extend(bytes.iter().filter(|byte| **byte != 0));
+    Ok(())
+}
+
+/// the reverse of [IdaGenericBufUnpack::read_tah], nothing is written if
+/// the `tattr` is zero and there are no extended attributes
+fn write_tah(
+    output: &mut Vec<u8>,
+    tattr: u16,
+    extended: &[TypeAttributeExt],
+) -> Result<()> {
+    let tattr = tattr_with_ext(tattr, extended)?;
+    if tattr == 0 {
+        return Ok(());
+    }
+    output.push(flag::tattr::TAH_BYTE as u8);
+    write_tattr_bytes(output, tattr);
+    write_tattr_ext(output, extended)
+}
+
+/// the reverse of [IdaGenericBufUnpack::read_sdacl], nothing is written if
+/// the `tattr` is zero and there are no extended attributes
+fn write_sdacl(
+    output: &mut Vec<u8>,
+    tattr: u16,
+    extended: &[TypeAttributeExt],
+) -> Result<()> {
+    let tattr = tattr_with_ext(tattr, extended)?;
+    match tattr {
+        0 => {}
+        // small values are encoded into the sdacl byte itself
+        1..=7 => {
+            let value = (tattr - 1) as u8;
+            output.push(0xC0 | (value & 1) | ((value & 6) << 3));
+        }
+        // the long version always include the 0x8 bit
+        _ if tattr & 0x8 != 0 => {
+            output.push(0xF1);
+            write_tattr_bytes(output, tattr);
+        }
+        _ => return Err(anyhow!("Unable to write {tattr:#x} as SDACL")),
+    }
+    write_tattr_ext(output, extended)
+}
+
+fn tattr_with_ext(tattr: u16, extended: &[TypeAttributeExt]) -> Result<u16> {
+    ensure!(
+        tattr & flag::tattr_ext::TAH_HASATTRS == 0,
+        "Extended type attributes flag set without attributes"
+    );
+    if extended.is_empty() {
+        Ok(tattr)
+    } else {
+        Ok(tattr | flag::tattr_ext::TAH_HASATTRS)
+    }
+}
+
+/// the reverse of the extended attributes at
+/// [IdaGenericUnpack::read_type_attribute]
+fn write_tattr_ext(
+    output: &mut Vec<u8>,
+    extended: &[TypeAttributeExt],
+) -> Result<()> {
+    if extended.is_empty() {
+        return Ok(());
+    }
+    let len = u16::try_from(extended.len())
+        .map_err(|_| anyhow!("Too many extended type attributes"))?;
+    write_dt(output, len)?;
+    for attr in extended {
+        write_dt_bytes(output, &attr.key)?;
+        write_dt_bytes(output, &attr.value)?;
+    }
+    Ok(())
+}
+
+fn write_tattr_bytes(output: &mut Vec<u8>, mut tattr: u16) {
+    loop {
+        let byte = (tattr & 0x7F) as u8;
+        tattr >>= 7;
+        if tattr == 0 {
+            output.push(byte);
+            return;
+        }
+        output.push(byte | 0x80);
+    }
+}
+
+/// the reverse of the `MAX_DECL_ALIGN` bits on the type attributes
+fn alignment_to_raw(alignment: Option<NonZeroU8>) -> Result<u16> {
+    match alignment {
+        None => Ok(0),
+        Some(align) if align.is_power_of_two() && align.get() <= 64 => {
+            Ok(align.trailing_zeros() as u16 + 1)
+        }
+        Some(align) => Err(anyhow!("Invalid alignment {align}")),
+    }
+}
+
+/// round `value` up to the next multiple of `align`
+///
+/// `align` don't need to be a power of two, and an `align` of zero is
+/// the same as no alignment, returning `value` unchanged.
+pub fn align_mem(value: u64, align: u64) -> u64 {
+    if align == 0 {
+        return value;
+    }
+    let align_diff = value % align;
+    if align_diff == 0 {
+        return value;
+    }
+    let result = value + (align - align_diff);
+    debug_assert!(result >= value && result.is_multiple_of(align));
+    result
+}
+
 pub fn ephemeral_til_header() -> TILSectionHeader {
     section::TILSectionHeader {
         format: 12,
@@ -813,6 +1585,96 @@ pub fn ephemeral_til_header() -> TILSectionHeader {
         type_ordinal_alias: None,
         is_universal: true,
         compiler_id: crate::id0::Compiler::Unknown,
+        compiler_unsure: false,
         cm: None,
     }
 }
+
+/// environment variables with the IDA installation directory, in order
+pub const IDA_DIR_ENV_VARS: [&str; 2] = ["IDADIR", "IDA_DIR"];
+
+/// candidate directories with the standard IDA TIL files, eg: for
+/// [TILSection::load_dependencies]
+///
+/// The IDA installation is taken from the environment variables
+/// [IDA_DIR_ENV_VARS] (`IDADIR`, then `IDA_DIR`), followed by the common
+/// install locations: `/opt/ida*`, `$HOME/ida*`, `/Applications/IDA*.app`
+/// and `C:\Program Files\IDA*`. For each installation, the `til` directory
+/// and its sub-directories (eg: `til/pc`, `til/arm`) are returned. Only
+/// existing directories are included.
+pub fn standard_til_dirs() -> Vec<std::path::PathBuf> {
+    use std::path::{Path, PathBuf};
+
+    fn installs_in(parent: &Path, output: &mut Vec<PathBuf>) {
+        let Ok(entries) = std::fs::read_dir(parent) else {
+            return;
+        };
+        let mut installs: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.to_lowercase().starts_with("ida"))
+            })
+            .map(|path| {
+                // macOS app bundles have the files inside the bundle
+                let bundle = path.join("Contents/MacOS");
+                if bundle.is_dir() {
+                    bundle
+                } else {
+                    path
+                }
+            })
+            .collect();
+        installs.sort();
+        output.extend(installs);
+    }
+
+    let mut installs: Vec<PathBuf> = IDA_DIR_ENV_VARS
+        .iter()
+        .filter_map(std::env::var_os)
+        .map(PathBuf::from)
+        .collect();
+    let home =
+        std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"));
+    for parent in [
+        Some(PathBuf::from("/opt")),
+        home.map(PathBuf::from),
+        Some(PathBuf::from("/Applications")),
+        Some(PathBuf::from(r"C:\Program Files")),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        installs_in(&parent, &mut installs);
+    }
+    standard_til_dirs_from(installs)
+}
+
+/// same as [standard_til_dirs], but only for the IDA installations
+/// directories in `ida_dirs`, in order
+pub fn standard_til_dirs_from(
+    ida_dirs: impl IntoIterator<Item = std::path::PathBuf>,
+) -> Vec<std::path::PathBuf> {
+    use std::path::PathBuf;
+
+    let mut result = vec![];
+    for install in ida_dirs {
+        let til_dir = install.join("til");
+        if !til_dir.is_dir() || result.contains(&til_dir) {
+            continue;
+        }
+        let mut sub_dirs: Vec<PathBuf> = std::fs::read_dir(&til_dir)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .collect();
+        sub_dirs.sort();
+        result.push(til_dir);
+        result.extend(sub_dirs);
+    }
+    result
+}
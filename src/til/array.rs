@@ -5,9 +5,9 @@ use crate::ida_reader::IdaGenericBufUnpack;
 use crate::til::{Type, TypeAttribute, TypeRaw};
 use crate::IDBString;
 
-use super::section::TILSectionHeader;
+use super::section::{TILSection, TILSectionHeader};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Array {
     pub alignment: Option<NonZeroU8>,
     pub base: u8,
@@ -36,6 +36,40 @@ impl Array {
             .map(Box::new)?,
         })
     }
+
+    pub(crate) fn write<'a>(
+        &'a self,
+        section: &TILSection,
+        output: &mut Vec<u8>,
+        fields: &mut Vec<Option<&'a IDBString>>,
+    ) -> anyhow::Result<()> {
+        use crate::til::flag::tf_array::*;
+        let nelem = self.nelem.map(NonZeroU16::get).unwrap_or(0);
+        if self.base == 0 {
+            output.push(BT_ARRAY | BTMT_NONBASED);
+            super::write_dt(output, nelem)?;
+        } else {
+            // the reverse of `read_da`, the values are swapped by the reader
+            let nelem = u8::try_from(nelem).map_err(|_| {
+                anyhow::anyhow!("Based Array with too many elements {nelem}")
+            })?;
+            output.push(BT_ARRAY);
+            output.extend([0x80, 0x80, 0x80, 0x80 | (nelem >> 4)]);
+            output.push(0x10 | (nelem & 0xF));
+            output.extend([
+                0x80,
+                0x80,
+                0x80 | (self.base >> 7),
+                0x80 | (self.base & 0x7F),
+            ]);
+        }
+        super::write_tah(
+            output,
+            super::alignment_to_raw(self.alignment)?,
+            &[],
+        )?;
+        self.elem_type.write(section, output, fields)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -51,6 +85,7 @@ impl ArrayRaw {
         input: &mut impl IdaGenericBufUnpack,
         header: &TILSectionHeader,
         metadata: u8,
+        depth: usize,
     ) -> anyhow::Result<Self> {
         use crate::til::flag::tattr::*;
         use crate::til::flag::tf_array::*;
@@ -90,7 +125,7 @@ impl ArrayRaw {
                 "unknown TypeAttribute ext {_extended:x?}"
             );
         }
-        let elem_type = TypeRaw::read(&mut *input, header)?;
+        let elem_type = TypeRaw::read_depth(&mut *input, header, depth)?;
         Ok(ArrayRaw {
             base,
             alignment,
@@ -1,12 +1,12 @@
 use std::num::NonZeroU8;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 
 use crate::ida_reader::IdaGenericBufUnpack;
 
 use super::TypeAttribute;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Bitfield {
     pub unsigned: bool,
     // TODO what a 0 width bitfield means? The start of a new byte-field?
@@ -27,6 +27,40 @@ pub struct Bitfield {
 }
 
 impl Bitfield {
+    /// mask of the field value, not shifted to the field position
+    pub fn mask(&self) -> u64 {
+        match self.width {
+            0 => 0,
+            64.. => u64::MAX,
+            width => (1 << width) - 1,
+        }
+    }
+
+    /// extract the field value from the storage, `shift` is the field
+    /// position in bits, the value is zero-extended
+    pub fn extract(&self, storage: u64, shift: u32) -> u64 {
+        storage.checked_shr(shift).unwrap_or(0) & self.mask()
+    }
+
+    /// extract the field value from the storage, sign-extended if the field
+    /// is signed
+    pub fn extract_signed(&self, storage: u64, shift: u32) -> i64 {
+        let value = self.extract(storage, shift);
+        if self.unsigned || self.width == 0 || self.width >= 64 {
+            return value as i64;
+        }
+        let unused_bits = 64 - u32::from(self.width);
+        ((value << unused_bits) as i64) >> unused_bits
+    }
+
+    /// replace the field value in the storage, extra bits in value are
+    /// ignored
+    pub fn insert(&self, storage: u64, shift: u32, value: u64) -> u64 {
+        let mask = self.mask().checked_shl(shift).unwrap_or(0);
+        let value = (value & self.mask()).checked_shl(shift).unwrap_or(0);
+        (storage & !mask) | value
+    }
+
     pub(crate) fn read(
         input: &mut impl IdaGenericBufUnpack,
         metadata: u8,
@@ -66,4 +100,21 @@ impl Bitfield {
             nbytes: nbytes.try_into().unwrap(),
         })
     }
+
+    pub(crate) fn write(&self, output: &mut Vec<u8>) -> Result<()> {
+        use super::flag::tf_complex::*;
+        let btmt = match self.nbytes.get() {
+            1 => BTMT_BFLDI8,
+            2 => BTMT_BFLDI16,
+            4 => BTMT_BFLDI32,
+            8 => BTMT_BFLDI64,
+            nbytes => return Err(anyhow!("Invalid Bitfield size {nbytes}")),
+        };
+        output.push(BT_BITFIELD | btmt);
+        let width = self
+            .width
+            .checked_mul(2)
+            .ok_or_else(|| anyhow!("Invalid Bitfield width {}", self.width))?;
+        super::write_dt(output, width | u16::from(self.unsigned))
+    }
 }
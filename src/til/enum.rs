@@ -1,13 +1,14 @@
+use std::collections::HashMap;
 use std::num::NonZeroU8;
 
 use crate::ida_reader::IdaGenericBufUnpack;
-use crate::til::{flag, TypeAttribute, TypeRaw, TypeVariantRaw};
+use crate::til::{flag, TypeAttributes, TypeRaw, TypeVariantRaw};
 use crate::IDBString;
 use anyhow::{anyhow, ensure};
 
 use super::section::TILSectionHeader;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Enum {
     pub is_signed: bool,
     pub is_unsigned: bool,
@@ -15,8 +16,7 @@ pub struct Enum {
     pub members: Vec<(Option<IDBString>, u64)>,
     pub groups: Option<Vec<u16>>,
     pub storage_size: Option<NonZeroU8>,
-    // TODO parse type attributes
-    //others: StructMemberRaw,
+    pub attributes: TypeAttributes,
 }
 impl Enum {
     pub(crate) fn new(
@@ -36,8 +36,198 @@ impl Enum {
             members,
             groups: value.groups,
             storage_size: value.storage_size,
+            attributes: value.attributes,
+        })
+    }
+
+    pub(crate) fn write<'a>(
+        &'a self,
+        output: &mut Vec<u8>,
+        fields: &mut Vec<Option<&'a IDBString>>,
+    ) -> anyhow::Result<()> {
+        use flag::tattr_enum::*;
+        use flag::tf_complex::*;
+        use flag::tf_enum::*;
+
+        output.push(BT_COMPLEX | BTMT_ENUM);
+        let member_num = u32::try_from(self.members.len())
+            .map_err(|_| anyhow!("Enum with too many members"))?;
+        super::write_dt_de(output, member_num)?;
+
+        let is_64 = self
+            .members
+            .iter()
+            .any(|(_, value)| *value > u32::MAX.into());
+        let mut tattr = 0;
+        for (is_set, flag) in [
+            (is_64, TAENUM_64BIT),
+            (self.is_signed, TAENUM_SIGNED),
+            (self.is_unsigned, TAENUM_UNSIGNED),
+        ] {
+            if is_set {
+                tattr |= flag;
+            }
+        }
+        super::write_tah(
+            output,
+            tattr | self.attributes.unknown_flags,
+            &self.attributes.extended,
+        )?;
+
+        let storage_size_raw = match self.storage_size.map(NonZeroU8::get) {
+            None => 0,
+            Some(size @ (1 | 2 | 4 | 8)) => size.trailing_zeros() as u8 + 1,
+            Some(size) => return Err(anyhow!("Invalid Enum size {size}")),
+        };
+        let output_format = match self.output_format {
+            EnumFormat::Hex => BTE_HEX,
+            EnumFormat::Char => BTE_CHAR,
+            EnumFormat::SignedDecimal => BTE_SDEC,
+            EnumFormat::UnsignedDecimal => BTE_UDEC,
+        };
+        let bitfield = if self.groups.is_some() {
+            BTE_BITFIELD
+        } else {
+            0
+        };
+        output.push(BTE_ALWAYS | output_format | bitfield | storage_size_raw);
+
+        // the group len is written before the first member of each group
+        let mut group_starts = HashMap::new();
+        if let Some(groups) = &self.groups {
+            let mut start = 0usize;
+            for group in groups {
+                ensure!(*group != 0, "Enum with an empty group");
+                group_starts.insert(start, *group);
+                start += usize::from(*group);
+            }
+            ensure!(
+                start == self.members.len(),
+                "Enum groups don't match the number of members"
+            );
+        }
+
+        let storage_size = self.storage_size.map(NonZeroU8::get).unwrap_or(4);
+        let mask = u64::MAX >> (u64::BITS - (u32::from(storage_size) * 8));
+        let mut low_acc: u32 = 0;
+        let mut high_acc: u32 = 0;
+        for (i, (name, value)) in self.members.iter().enumerate() {
+            ensure!(
+                value & !mask == 0,
+                "Enum member value {value:#x} is bigger then the enum"
+            );
+            fields.push(name.as_ref());
+            if let Some(group) = group_starts.get(&i) {
+                super::write_dt(output, *group)?;
+            }
+            // values are written as deltas from the previous one
+            let (low, high) = (*value as u32, (*value >> 32) as u32);
+            super::write_de(output, low.wrapping_sub(low_acc));
+            if is_64 {
+                super::write_de(output, high.wrapping_sub(high_acc));
+            }
+            low_acc = low;
+            high_acc = high;
+        }
+        Ok(())
+    }
+
+    /// true if this is a bitmask enum, with the members split in groups
+    pub fn is_bitmask(&self) -> bool {
+        self.groups.is_some()
+    }
+
+    /// the groups of a bitmask enum, empty if this is not a bitmask enum
+    pub fn bitmask_groups(&self) -> impl Iterator<Item = EnumGroup<'_>> {
+        let mut members = &self.members[..];
+        self.groups
+            .iter()
+            .flatten()
+            .map(move |group_len| {
+                let group_len = usize::from(*group_len).min(members.len());
+                let (group, rest) = members.split_at(group_len);
+                members = rest;
+                group
+            })
+            .filter_map(|group| match group {
+                [] => None,
+                // single bit group, the value is also the mask
+                [(name, flag)] => Some(EnumGroup {
+                    mask: *flag,
+                    mask_name: name.as_ref(),
+                    members: &[],
+                }),
+                // the first member of the group is the mask
+                [(mask_name, mask), values @ ..] => Some(EnumGroup {
+                    mask: *mask,
+                    mask_name: mask_name.as_ref(),
+                    members: values,
+                }),
+            })
+    }
+
+    /// the mask of the member at `idx`, only available for bitmask enums
+    pub fn member_mask(&self, idx: usize) -> Option<u64> {
+        let groups = self.groups.as_ref()?;
+        let mut start = 0usize;
+        for group_len in groups {
+            let end = start + usize::from(*group_len);
+            if idx < end {
+                // the mask is the first member of the group
+                return self.members.get(start).map(|(_, mask)| *mask);
+            }
+            start = end;
+        }
+        None
+    }
+
+    /// find the name of the member with this value, if multiple members
+    /// share the value, the first named one is returned
+    pub fn name_for_value(&self, value: u64) -> Option<&IDBString> {
+        self.members.iter().find_map(|(name, member)| {
+            (*member == value).then_some(name.as_ref()).flatten()
         })
     }
+
+    /// find the names of the members that compose this value, for bitmask
+    /// enums each group is checked independently, otherwise it's the same
+    /// as [Enum::name_for_value]
+    pub fn names_for_value(&self, value: u64) -> Vec<&IDBString> {
+        if !self.is_bitmask() {
+            return self.name_for_value(value).into_iter().collect();
+        }
+        let mut result = vec![];
+        for group in self.bitmask_groups() {
+            if group.is_single_bit() {
+                if group.mask != 0 && value & group.mask == group.mask {
+                    result.extend(group.mask_name);
+                }
+                continue;
+            }
+            let masked = value & group.mask;
+            result.extend(group.members.iter().find_map(|(name, member)| {
+                (*member == masked).then_some(name.as_ref()).flatten()
+            }));
+        }
+        result
+    }
+}
+
+/// a group of members from a bitmask enum
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EnumGroup<'a> {
+    /// the mask of all the values of this group
+    pub mask: u64,
+    pub mask_name: Option<&'a IDBString>,
+    /// the values of this group, empty for single bit groups
+    pub members: &'a [(Option<IDBString>, u64)],
+}
+
+impl EnumGroup<'_> {
+    /// groups with a single member, the flag is also the mask
+    pub fn is_single_bit(&self) -> bool {
+        self.members.is_empty()
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -48,6 +238,7 @@ pub(crate) struct EnumRaw {
     groups: Option<Vec<u16>>,
     members: Vec<u64>,
     storage_size: Option<NonZeroU8>,
+    attributes: TypeAttributes,
 }
 
 impl EnumRaw {
@@ -55,6 +246,7 @@ impl EnumRaw {
     pub(crate) fn read(
         input: &mut impl IdaGenericBufUnpack,
         header: &TILSectionHeader,
+        depth: usize,
     ) -> anyhow::Result<TypeVariantRaw> {
         use flag::tattr_enum::*;
         use flag::tf_enum::*;
@@ -62,7 +254,7 @@ impl EnumRaw {
         let Some(member_num) = input.read_dt_de()? else {
             // is ref
             // InnerRef fb47f2c2-3c08-4d40-b7ab-3c7736dce31d 0x4803b4
-            let ref_type = TypeRaw::read_ref(&mut *input, header)?;
+            let ref_type = TypeRaw::read_ref(&mut *input, header, depth)?;
             // TODO ensure all bits from sdacl are parsed
             let _taenum_bits = input.read_sdacl()?;
             let TypeVariantRaw::Typedef(ref_type) = ref_type.variant else {
@@ -74,11 +266,9 @@ impl EnumRaw {
         let mut is_64 = false;
         let mut is_signed = false;
         let mut is_unsigned = false;
-        if let Some(TypeAttribute {
-            tattr,
-            extended: _extended,
-        }) = input.read_tah()?
-        {
+        let mut attributes = TypeAttributes::default();
+        if let Some(attribute) = input.read_tah()? {
+            let tattr = attribute.tattr;
             // TODO enum have an align field (MAX_DECL_ALIGN) in tattr?
             is_64 = tattr & TAENUM_64BIT != 0;
             is_signed = tattr & TAENUM_SIGNED != 0;
@@ -93,10 +283,9 @@ impl EnumRaw {
                 !(is_signed && is_unsigned),
                 "Enum can't be signed and unsigned at the same time"
             );
-            #[cfg(feature = "restrictive")]
-            ensure!(
-                _extended.is_none(),
-                "Unable to parse extended attributes for Enum"
+            attributes = TypeAttributes::from_raw(
+                attribute,
+                TAENUM_64BIT | TAENUM_SIGNED | TAENUM_UNSIGNED,
             );
         }
 
@@ -152,6 +341,7 @@ impl EnumRaw {
                     // Allowed at InnerRef fb47f2c2-3c08-4d40-b7ab-3c7736dce31d 0x452527 deserialize_enum
                     if group_acc == 0 {
                         group_acc = input.read_dt()?;
+                        ensure!(group_acc != 0, "Invalid empty enum group");
                         groups.push(group_acc);
                     }
                     group_acc -= 1;
@@ -174,11 +364,12 @@ impl EnumRaw {
             members,
             groups,
             storage_size,
+            attributes,
         }))
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum EnumFormat {
     Char,
     Hex,
@@ -7,6 +7,8 @@ type TilT = u16;
 /// TypeAtt Type flags
 type TattrT = u16;
 type CmT = u8;
+/// Compiler id
+type CompT = u8;
 
 /// multi-use
 pub const RESERVED_BYTE: TypeT = 0xFF;
@@ -347,7 +349,7 @@ pub mod tf_enum {
     ///   - 'de' mask (has name)
     ///   - 'dt' cnt
     ///   - cnt records of 'de' values
-    ///      (cnt CAN be 0)
+    ///     (cnt CAN be 0)
     ///
     /// NOTE: delta for ALL subsegment is ONE
     pub const BTE_BITFIELD: BteT = 0x10;
@@ -561,6 +563,35 @@ pub mod til {
     pub const TIL_SLD: TilT = 0x0100;
 }
 
+/// Compiler ids, the values of [crate::id0::Compiler]
+pub mod comp {
+    use super::CompT;
+    /// mask for the compiler id, without the [COMP_UNSURE] bit
+    pub const COMP_MASK: CompT = 0x0F;
+    /// Unknown
+    pub const COMP_UNK: CompT = 0x00;
+    /// Visual C++
+    pub const COMP_MS: CompT = 0x01;
+    /// Borland C++
+    pub const COMP_BC: CompT = 0x02;
+    /// Watcom C++
+    pub const COMP_WATCOM: CompT = 0x03;
+    /// GNU C++
+    pub const COMP_GNU: CompT = 0x06;
+    /// Visual Age C++
+    pub const COMP_VISAGE: CompT = 0x07;
+    /// Delphi
+    pub const COMP_BP: CompT = 0x08;
+    /// uncertain compiler id
+    pub const COMP_UNSURE: CompT = 0x80;
+
+    /// the name of the compiler id, as printed by IDA, `?` if unknown. The
+    /// [COMP_UNSURE] bit is ignored
+    pub fn name(comp: CompT) -> &'static str {
+        crate::id0::Compiler::from_value(comp & COMP_MASK).name()
+    }
+}
+
 /// Calling convention & Model
 pub mod cm {
     use super::CmT;
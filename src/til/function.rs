@@ -2,19 +2,20 @@ use std::collections::HashMap;
 use std::num::NonZeroU8;
 
 use crate::ida_reader::{IdaGenericBufUnpack, IdaGenericUnpack};
-use crate::til::{Basic, Type, TypeRaw};
+use crate::til::{Basic, Type, TypeRaw, TypeVariant};
 use crate::IDBString;
 use anyhow::{anyhow, ensure, Context, Result};
 
-use super::section::TILSectionHeader;
+use super::section::{TILSection, TILSectionHeader};
 use super::TypeVariantRaw;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Function {
     pub calling_convention: Option<CallingConvention>,
     pub ret: Box<Type>,
     pub args: Vec<(Option<IDBString>, Type, Option<ArgLoc>)>,
     pub retloc: Option<ArgLoc>,
+    pub spoiled: Vec<SpoiledReg>,
 
     pub method: Option<CallMethod>,
     pub is_noret: bool,
@@ -60,6 +61,7 @@ impl Function {
             args,
             method: value.method,
             retloc: value.retloc,
+            spoiled: value.spoiled,
             is_noret: value.is_noret,
             is_pure: value.is_pure,
             is_high: value.is_high,
@@ -70,6 +72,153 @@ impl Function {
             is_destructor: value.is_destructor,
         })
     }
+
+    /// the explicit location of the return value, only available for
+    /// functions with a user defined calling convention, eg. `__usercall`
+    pub fn return_location(&self) -> Option<&ArgLoc> {
+        self.retloc.as_ref().filter(|loc| **loc != ArgLoc::None)
+    }
+
+    /// the registers spoiled by the function, eg. `__spoils<ecx>`
+    pub fn spoiled_regs(&self) -> &[SpoiledReg] {
+        &self.spoiled
+    }
+
+    /// all the arguments, with the name, type and location of each
+    pub fn arguments(&self) -> impl Iterator<Item = FunctionArg<'_>> {
+        self.args.iter().map(|(name, ty, location)| FunctionArg {
+            name: name.as_ref(),
+            ty,
+            location: location.as_ref(),
+        })
+    }
+
+    pub(crate) fn write<'a>(
+        &'a self,
+        section: &TILSection,
+        output: &mut Vec<u8>,
+        fields: &mut Vec<Option<&'a IDBString>>,
+    ) -> Result<()> {
+        use super::flag::cm::cc::CM_CC_UNKNOWN;
+        use super::flag::tf_func::*;
+        let method = match self.method {
+            None => BTMT_DEFCALL,
+            Some(CallMethod::Near) => BTMT_NEARCALL,
+            Some(CallMethod::Far) => BTMT_FARCALL,
+            Some(CallMethod::Int) => BTMT_INTCALL,
+        };
+        output.push(BT_FUNC | method);
+
+        // the reverse of `read_cc`
+        let flags_lower = [
+            (self.is_noret, BFA_NORET),
+            (self.is_pure, BFA_PURE),
+            (self.is_high, BFA_HIGH),
+            (self.is_static, BFA_STATIC),
+            (self.is_virtual, BFA_VIRTUAL),
+        ]
+        .into_iter()
+        .filter_map(|(is_set, flag)| is_set.then_some(flag))
+        .fold(0u8, |acc, flag| acc | flag);
+        // TODO find those flags, same as the parser
+        let flags_upper = [
+            (self.is_const, 0x4),
+            (self.is_constructor, 0x8),
+            (self.is_destructor, 0x10),
+        ]
+        .into_iter()
+        .filter_map(|(is_set, flag)| is_set.then_some(flag))
+        .fold(0u8, |acc, flag: u8| acc | flag);
+        let have_spoiled = !self.spoiled.is_empty();
+        if flags_lower != 0 || flags_upper != 0 || have_spoiled {
+            output.extend([0xAF, 0x80 | u8::from(have_spoiled)]);
+            let flags = u32::from(have_spoiled)
+                | u32::from(flags_lower) << 1
+                | u32::from(flags_upper) << 8;
+            super::write_de(output, flags);
+            if have_spoiled {
+                let spoiled_len = u16::try_from(self.spoiled.len())
+                    .map_err(|_| anyhow!("Too many spoiled registers"))?;
+                super::write_dt(output, spoiled_len)?;
+                for spoiled in &self.spoiled {
+                    spoiled.write(output)?;
+                }
+            }
+        }
+        output.push(
+            self.calling_convention
+                .map(|cc| (cc as u8) << 4)
+                .unwrap_or(CM_CC_UNKNOWN),
+        );
+
+        self.ret.write(section, output, fields)?;
+        let is_special_pe = self
+            .calling_convention
+            .map(CallingConvention::is_special_pe)
+            .unwrap_or(false);
+        let have_retloc = is_special_pe
+            && !matches!(
+                &self.ret.type_variant,
+                TypeVariant::Basic(Basic::Void)
+            );
+        match (have_retloc, &self.retloc) {
+            (true, Some(retloc)) => retloc.write(output)?,
+            (false, None) => {}
+            (true, None) => return Err(anyhow!("Function without a retloc")),
+            (false, Some(_)) => {
+                return Err(anyhow!("Function with an unexpected retloc"))
+            }
+        }
+
+        if self.calling_convention == Some(CallingConvention::Voidarg) {
+            ensure!(self.args.is_empty(), "Voidarg Function with arguments");
+            return Ok(());
+        }
+        let args_len = u16::try_from(self.args.len())
+            .map_err(|_| anyhow!("Function with too many arguments"))?;
+        super::write_dt(output, args_len)?;
+        for (name, arg, argloc) in &self.args {
+            fields.push(name.as_ref());
+            arg.write(section, output, fields)?;
+            match (is_special_pe, argloc) {
+                (true, Some(argloc)) => argloc.write(output)?,
+                (false, None) => {}
+                (true, None) => {
+                    return Err(anyhow!("Function argument without an argloc"))
+                }
+                (false, Some(_)) => {
+                    return Err(anyhow!(
+                        "Function argument with an unexpected argloc"
+                    ))
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// an argument of a [Function], see [Function::arguments]
+#[derive(Debug, Clone, Copy)]
+pub struct FunctionArg<'a> {
+    name: Option<&'a IDBString>,
+    ty: &'a Type,
+    location: Option<&'a ArgLoc>,
+}
+
+impl<'a> FunctionArg<'a> {
+    pub fn name(&self) -> Option<&'a IDBString> {
+        self.name
+    }
+
+    pub fn ty(&self) -> &'a Type {
+        self.ty
+    }
+
+    /// the explicit location of the argument, only available for functions
+    /// with a user defined calling convention, eg. `__usercall`
+    pub fn location(&self) -> Option<&'a ArgLoc> {
+        self.location.filter(|loc| **loc != ArgLoc::None)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -77,6 +226,7 @@ pub(crate) struct FunctionRaw {
     pub ret: Box<TypeRaw>,
     pub args: Vec<(TypeRaw, Option<ArgLoc>)>,
     pub retloc: Option<ArgLoc>,
+    pub spoiled: Vec<SpoiledReg>,
     pub calling_convention: Option<CallingConvention>,
 
     pub method: Option<CallMethod>,
@@ -90,7 +240,7 @@ pub(crate) struct FunctionRaw {
     pub is_destructor: bool,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ArgLoc {
     // TODO add those to flags
     // ::ALOC_STACK
@@ -118,7 +268,7 @@ pub enum ArgLoc {
     // TODO is possible to know the custom impl len?
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ArgLocDist {
     pub info: u16,
     pub off: u16,
@@ -132,6 +282,7 @@ impl FunctionRaw {
         input: &mut impl IdaGenericBufUnpack,
         header: &TILSectionHeader,
         metadata: u8,
+        depth: usize,
     ) -> Result<Self> {
         use super::flag::tf_func::*;
         let method = match metadata {
@@ -143,7 +294,7 @@ impl FunctionRaw {
         };
 
         // TODO InnerRef fb47f2c2-3c08-4d40-b7ab-3c7736dce31d 0x473bf1 print_til_type
-        let (cc, flags, _spoiled) = read_cc(&mut *input)?;
+        let (cc, flags, spoiled) = read_cc(&mut *input)?;
         let cc = CallingConvention::from_cm_raw(cc)?;
 
         // TODO investigate why this don't hold true
@@ -159,7 +310,7 @@ impl FunctionRaw {
         // TODO find those in flags
         let have_spoiled = flags & 0x0001 != 0;
         if !have_spoiled {
-            ensure!(_spoiled.is_empty());
+            ensure!(spoiled.is_empty());
         }
         let flags_lower = ((flags & 0xFF) >> 1) as u8;
 
@@ -188,8 +339,8 @@ impl FunctionRaw {
             flags_upper & !(BFA_CONST | BFA_CONSTRUCTOR | BFA_DESTRUCTOR) == 0
         );
 
-        let ret =
-            TypeRaw::read(&mut *input, header).context("Return Argument")?;
+        let ret = TypeRaw::read_depth(&mut *input, header, depth)
+            .context("Return Argument")?;
         // TODO double check documentation for [flag::tf_func::BT_FUN]
         let is_special_pe =
             cc.map(CallingConvention::is_special_pe).unwrap_or(false);
@@ -205,6 +356,7 @@ impl FunctionRaw {
             ret: Box::new(ret),
             args: vec![],
             retloc,
+            spoiled,
 
             method,
             is_noret,
@@ -229,7 +381,7 @@ impl FunctionRaw {
                     // TODO what is this?
                     let _flags = input.read_de()?;
                 }
-                let tinfo = TypeRaw::read(&mut *input, header)
+                let tinfo = TypeRaw::read_depth(&mut *input, header, depth)
                     .with_context(|| format!("Argument Type {i}"))?;
                 let argloc = is_special_pe
                     .then(|| ArgLoc::read(&mut *input))
@@ -310,6 +462,72 @@ impl ArgLoc {
             }
         }
     }
+
+    // the reverse of [ArgLoc::read]
+    fn write(&self, output: &mut Vec<u8>) -> Result<()> {
+        use super::flag::tf_func::argloc::*;
+        use super::{write_de, write_dt};
+        match self {
+            Self::None => {
+                output.push(0xFF);
+                write_dt(output, ALOC_NONE.into())?;
+            }
+            Self::Stack(offset) => {
+                output.push(0xFF);
+                write_dt(output, ALOC_STACK.into())?;
+                write_de(output, *offset);
+            }
+            Self::Dist(dist) => {
+                ensure!(dist.len() <= 7, "ArgLoc with too many dist values");
+                output.push(0xFF);
+                write_dt(
+                    output,
+                    u16::from(ALOC_DIST) | (dist.len() as u16) << 5,
+                )?;
+                for ArgLocDist { info, off, size } in dist {
+                    write_dt(output, *info)?;
+                    write_dt(output, *off)?;
+                    write_dt(output, *size)?;
+                }
+            }
+            // small registers are written in a single byte
+            Self::Reg1(reg @ 0..=0x7E) => output.push(*reg as u8 + 1),
+            Self::Reg1(reg) => {
+                let reg = u16::try_from(*reg)
+                    .map_err(|_| anyhow!("Invalid ArgLoc Reg1 {reg:#x}"))?;
+                output.push(0xFF);
+                write_dt(output, ALOC_REG1.into())?;
+                write_dt(output, reg)?;
+            }
+            Self::Reg2(reg) => {
+                let (low, high) = (reg & 0xFFFF, reg >> 16);
+                match (low, high) {
+                    // the short form, register pair in two bytes
+                    (1..=0x7E, 0..=0xFE) => {
+                        output.extend([0x80 | low as u8, high as u8 + 1])
+                    }
+                    (_, 0) => {
+                        output.push(0xFF);
+                        write_dt(output, ALOC_REG2.into())?;
+                        write_dt(output, low as u16)?;
+                    }
+                    _ => return Err(anyhow!("Invalid ArgLoc Reg2 {reg:#x}")),
+                }
+            }
+            Self::RRel { reg, off } => {
+                output.push(0xFF);
+                write_dt(output, ALOC_RREL.into())?;
+                write_dt(output, *reg)?;
+                write_de(output, *off);
+            }
+            Self::Static(value) => {
+                output.push(0xFF);
+                write_dt(output, ALOC_STATIC.into())?;
+                write_de(output, *value);
+            }
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -343,6 +561,13 @@ pub enum CallingConvention {
 }
 
 impl CallingConvention {
+    /// the calling convention of the `cm` byte, only the `CM_CC_MASK` bits are
+    /// used, `None` if unknown or if the bits have no calling convention
+    /// (`CM_CC_INVALID` or `CM_CC_SPOILED`)
+    pub fn from_cm_byte(cm: u8) -> Option<Self> {
+        Self::from_cm_raw(cm).ok().flatten()
+    }
+
     // InnerRef fb47f2c2-3c08-4d40-b7ab-3c7736dce31d 0x40b860
     pub(crate) fn from_cm_raw(cm: u8) -> Result<Option<Self>> {
         use super::flag::cm::cc::*;
@@ -376,6 +601,25 @@ impl CallingConvention {
         }))
     }
 
+    /// the name of the calling convention, as printed by IDA
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Voidarg => "voidarg",
+            Self::Cdecl => "cdecl",
+            Self::Ellipsis => "ellipsis",
+            Self::Stdcall => "stdcall",
+            Self::Pascal => "pascal",
+            Self::Fastcall => "fastcall",
+            Self::Thiscall => "thiscall",
+            Self::Swift => "swift",
+            Self::Golang => "golang",
+            Self::Userpurge => "userpurge",
+            Self::Uservars => "uservars",
+            Self::Usercall => "usercall",
+            Self::Reserved3 => "ccreserved3",
+        }
+    }
+
     pub const fn is_special_pe(self) -> bool {
         matches!(self, Self::Uservars | Self::Userpurge | Self::Usercall)
     }
@@ -487,11 +731,38 @@ pub enum CallMethod {
     Int,
 }
 
+/// register spoiled by a function, and it's size
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpoiledReg {
+    /// the processor specific register number
+    pub reg: u16,
+    pub size: u8,
+}
+
+impl SpoiledReg {
+    // the reverse of `read_cc_spoiled`
+    fn write(&self, output: &mut Vec<u8>) -> Result<()> {
+        match (self.reg, self.size) {
+            // small registers are written in a single byte
+            (reg @ 0..=0xE, size @ 1..=8) => {
+                output.push((size - 1) << 4 | (reg as u8 + 1))
+            }
+            (reg @ 0..=0x7E, size) => output.extend([0x80 | reg as u8, size]),
+            (reg, size) => {
+                output.push(0xFF);
+                super::write_dt(output, reg)?;
+                output.push(size);
+            }
+        }
+        Ok(())
+    }
+}
+
 // InnerRef fb47f2c2-3c08-4d40-b7ab-3c7736dce31d 0x476e60
 /// [BT_FUNC](https://hex-rays.com/products/ida/support/sdkdoc/group__tf__func.html#ga7b7fee21f21237beb6d91e854410e0fa)
 fn read_cc(
     input: &mut impl IdaGenericBufUnpack,
-) -> Result<(u8, u16, Vec<(u16, u8)>)> {
+) -> Result<(u8, u16, Vec<SpoiledReg>)> {
     let mut cc = input.read_u8()?;
     // TODO find the flag for that
     if cc & 0xF0 != 0xA0 {
@@ -538,7 +809,7 @@ fn read_cc(
 fn read_cc_spoiled(
     input: &mut impl IdaGenericBufUnpack,
     nspoiled: u16,
-    spoiled: &mut Vec<(u16, u8)>,
+    spoiled: &mut Vec<SpoiledReg>,
 ) -> Result<()> {
     spoiled.reserve(nspoiled.into());
     for _i in 0..nspoiled {
@@ -551,7 +822,7 @@ fn read_cc_spoiled(
                 (b & 0x7F).into()
             };
             let size = input.read_u8()?;
-            spoiled.push((reg, size))
+            spoiled.push(SpoiledReg { reg, size })
         } else {
             let size = (b >> 4) + 1;
             // TODO what if (b & 0xF) == 0?
@@ -561,7 +832,10 @@ fn read_cc_spoiled(
                 .ok_or_else(|| anyhow!("invalid spoiled reg value"))?;
             #[cfg(not(feature = "restrictive"))]
             let reg = (b & 0xF).saturating_sub(1);
-            spoiled.push((reg.into(), size))
+            spoiled.push(SpoiledReg {
+                reg: reg.into(),
+                size,
+            })
         }
     }
     Ok(())
@@ -6,9 +6,9 @@ use crate::ida_reader::IdaGenericBufUnpack;
 use crate::til::{Type, TypeAttribute, TypeRaw};
 use crate::IDBString;
 
-use super::section::TILSectionHeader;
+use super::section::{TILSection, TILSectionHeader};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Pointer {
     pub closure: PointerType,
     pub modifier: Option<PointerModifier>,
@@ -56,9 +56,63 @@ impl Pointer {
             typ,
         })
     }
+
+    /// the pointed type
+    pub fn pointee(&self) -> &Type {
+        &self.typ
+    }
+
+    /// the parent type and delta, for `__shifted(parent, delta)` pointers
+    pub fn shifted_parent(&self) -> Option<(&Type, u32)> {
+        self.shifted
+            .as_ref()
+            .map(|(parent, delta)| (&**parent, *delta))
+    }
+
+    pub(crate) fn write<'a>(
+        &'a self,
+        section: &TILSection,
+        output: &mut Vec<u8>,
+        fields: &mut Vec<Option<&'a IDBString>>,
+    ) -> Result<()> {
+        use crate::til::flag::tattr_ptr::*;
+        use crate::til::flag::tf_ptr::*;
+        // the closure and shifted types don't receive fields from the parser
+        let mut no_fields = vec![];
+        match &self.closure {
+            PointerType::Default => output.push(BT_PTR | BTMT_DEFPTR),
+            PointerType::Far => output.push(BT_PTR | BTMT_FAR),
+            PointerType::Near => output.push(BT_PTR | BTMT_NEAR),
+            PointerType::Closure(closure) => {
+                output.extend([BT_PTR | BTMT_CLOSURE, 0xFF]);
+                closure.write(section, output, &mut no_fields)?;
+            }
+            PointerType::PointerBased(ptr) => {
+                // TODO find the meaning of the first byte, only 0xFF is
+                // special for the parser
+                output.extend([BT_PTR | BTMT_CLOSURE, 0x01, *ptr]);
+            }
+        }
+        let mut tattr = match self.modifier {
+            None => 0,
+            Some(PointerModifier::Ptr32) => TAPTR_PTR32,
+            Some(PointerModifier::Ptr64) => TAPTR_PTR64,
+            Some(PointerModifier::Restricted) => TAPTR_RESTRICT,
+        };
+        if self.shifted.is_some() {
+            tattr |= TAPTR_SHIFTED;
+        }
+        super::write_tah(output, tattr, &[])?;
+        self.typ.write(section, output, fields)?;
+        if let Some((shifted, value)) = &self.shifted {
+            shifted.write(section, output, &mut no_fields)?;
+            super::write_de(output, *value);
+        }
+        Ok(())
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PointerType {
     Closure(Box<Type>),
     PointerBased(u8),
@@ -90,7 +144,7 @@ impl PointerType {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PointerModifier {
     Ptr32,
     Ptr64,
@@ -112,6 +166,7 @@ impl PointerRaw {
         input: &mut impl IdaGenericBufUnpack,
         header: &TILSectionHeader,
         metadata: u8,
+        depth: usize,
     ) -> Result<Self> {
         use crate::til::flag::tattr::*;
         use crate::til::flag::tattr_ptr::*;
@@ -120,7 +175,7 @@ impl PointerRaw {
         // InnerRef fb47f2c2-3c08-4d40-b7ab-3c7736dce31d 0x459b54
         let closure = match metadata {
             BTMT_DEFPTR => PointerTypeRaw::Default,
-            BTMT_CLOSURE => PointerTypeRaw::read(&mut *input, header)?,
+            BTMT_CLOSURE => PointerTypeRaw::read(&mut *input, header, depth)?,
             // TODO find the meaning of this
             BTMT_FAR => PointerTypeRaw::Far,
             BTMT_NEAR => PointerTypeRaw::Near,
@@ -154,12 +209,12 @@ impl PointerRaw {
             }
         };
 
-        let typ = TypeRaw::read(&mut *input, header)?;
+        let typ = TypeRaw::read_depth(&mut *input, header, depth)?;
         // InnerRef fb47f2c2-3c08-4d40-b7ab-3c7736dce31d 0x459bc6
         let shifted = is_shifted
             .then(|| -> Result<_> {
                 // TODO allow typedef only?
-                let typ = TypeRaw::read(&mut *input, header)?;
+                let typ = TypeRaw::read_depth(&mut *input, header, depth)?;
                 let value = input.read_de()?;
                 Ok((Box::new(typ), value))
             })
@@ -198,11 +253,12 @@ impl PointerTypeRaw {
     fn read(
         input: &mut impl IdaGenericBufUnpack,
         header: &TILSectionHeader,
+        depth: usize,
     ) -> Result<Self> {
         let closure_type = input.read_u8()?;
         if closure_type == 0xFF {
             // InnerRef fb47f2c2-3c08-4d40-b7ab-3c7736dce31d 0x473b5a
-            let closure = TypeRaw::read(&mut *input, header)?;
+            let closure = TypeRaw::read_depth(&mut *input, header, depth)?;
             Ok(Self::Closure(Box::new(closure)))
         } else {
             // InnerRef fb47f2c2-3c08-4d40-b7ab-3c7736dce31d 0x4739f6
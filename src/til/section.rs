@@ -1,13 +1,21 @@
 use crate::id0::{Compiler, Id0TilOrd};
 use crate::ida_reader::{IdaGenericBufUnpack, IdaGenericUnpack};
-use crate::til::{flag, TILMacro, TILTypeInfo, TILTypeInfoRaw};
-use crate::{IDBSectionCompression, IDBString};
-use anyhow::{anyhow, ensure, Result};
+use crate::til::{
+    flag, TILMacro, TILTypeInfo, TILTypeInfoRaw, TypeVariant, Typeref,
+    TyperefValue,
+};
+use crate::{
+    IDBParser, IDBSectionCompression, IDBString, ParseReport, Warning,
+};
+use anyhow::{anyhow, ensure, Context, Result};
 use serde::{Deserialize, Serialize};
 
+use std::collections::HashSet;
 use std::fmt::Debug;
-use std::io::{BufReader, Read, Write};
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, Write};
 use std::num::NonZeroU8;
+use std::path::{Path, PathBuf};
 
 use super::function::{CCModel, CCPtrSize, CallingConvention};
 
@@ -17,7 +25,9 @@ pub const TIL_SECTION_MAGIC: &[u8; 6] = b"IDATIL";
 #[derive(Debug, Clone)]
 pub struct TILSection {
     pub header: TILSectionHeader,
+    /// the symbols, in the same order of the on-disk bucket
     pub symbols: Vec<TILTypeInfo>,
+    /// the types, in the same order of the on-disk bucket
     pub types: Vec<TILTypeInfo>,
     pub macros: Option<Vec<TILMacro>>,
 }
@@ -41,6 +51,8 @@ pub struct TILSectionHeader {
     pub dependencies: Vec<IDBString>,
     /// the compiler used to generated types
     pub compiler_id: Compiler,
+    /// the compiler id is only a guess, AKA `COMP_UNSURE`
+    pub compiler_unsure: bool,
     /// default calling convention
     pub cc: Option<CallingConvention>,
     /// default calling ptr size
@@ -60,6 +72,13 @@ pub struct TILSectionHeader {
     pub is_universal: bool,
 }
 
+/// the value of an ordinal, the type itself or an alias to other ordinal
+#[derive(Debug, Clone, Copy)]
+pub enum TILOrdType<'a> {
+    Type { idx: usize, ty: &'a TILTypeInfo },
+    Alias { target: u64 },
+}
+
 #[derive(Debug, Clone)]
 pub struct TILSectionExtendedSizeofInfo {
     pub size_short: NonZeroU8,
@@ -67,6 +86,22 @@ pub struct TILSectionExtendedSizeofInfo {
     pub size_long_long: NonZeroU8,
 }
 
+/// The compiler and the sizes of the basic types used by the section
+#[derive(Debug, Clone, Copy)]
+pub struct TILCompilerInfo {
+    pub compiler: Compiler,
+    /// near and far pointer sizes, if specified
+    pub ptr_size: Option<CCPtrSize>,
+    pub def_align: Option<NonZeroU8>,
+    pub size_bool: NonZeroU8,
+    pub size_short: NonZeroU8,
+    pub size_int: NonZeroU8,
+    pub size_long: NonZeroU8,
+    pub size_long_long: NonZeroU8,
+    pub size_enum: Option<NonZeroU8>,
+    pub size_long_double: Option<NonZeroU8>,
+}
+
 #[derive(Debug, Clone)]
 pub struct TILSectionHeaderRaw {
     pub format: u32,
@@ -104,18 +139,76 @@ impl TILSectionRaw {
     pub(crate) fn read(
         input: &mut impl IdaGenericBufUnpack,
         compress: IDBSectionCompression,
+        report: &mut ParseReport,
+    ) -> Result<Self> {
+        match compress {
+            IDBSectionCompression::None => Self::read_inner(input, report),
+            IDBSectionCompression::Zlib => {
+                let mut input =
+                    BufReader::new(flate2::bufread::ZlibDecoder::new(input));
+                Self::read_inner(&mut input, report)
+            }
+        }
+    }
+
+    fn read_inner(
+        input: &mut impl IdaGenericBufUnpack,
+        report: &mut ParseReport,
     ) -> Result<Self> {
+        let mut header = Self::read_section_header(&mut *input)?;
+
+        let symbols = Self::read_bucket(&mut *input, &header, None, report)?;
+
+        // TODO create an ordinal -> type mapping, to make sure the ordinals are not duplicated
+        // InnerRef fb47f2c2-3c08-4d40-b7ab-3c7736dce31d 0x42e292
+        let (next_ordinal, type_ordinal_alias) =
+            Self::read_next_ordinal_and_alias(&mut *input, &header)?;
+        header.type_ordinal_alias = type_ordinal_alias;
+        let types =
+            Self::read_bucket(&mut *input, &header, next_ordinal, report)?;
+        let macros = header
+            .flags
+            .has_macro_table()
+            .then(|| Self::read_macros(&mut *input, &header, report))
+            .transpose()?;
+
+        Ok(Self {
+            symbols,
+            types,
+            macros,
+            header,
+        })
+    }
+
+    pub(crate) fn read_header_only(
+        input: &mut impl IdaGenericBufUnpack,
+        compress: IDBSectionCompression,
+    ) -> Result<TILSectionHeader> {
         match compress {
-            IDBSectionCompression::None => Self::read_inner(input),
+            IDBSectionCompression::None => Self::read_header_only_inner(input),
             IDBSectionCompression::Zlib => {
                 let mut input =
                     BufReader::new(flate2::bufread::ZlibDecoder::new(input));
-                Self::read_inner(&mut input)
+                Self::read_header_only_inner(&mut input)
             }
         }
     }
 
-    fn read_inner(input: &mut impl IdaGenericBufUnpack) -> Result<Self> {
+    fn read_header_only_inner(
+        input: &mut impl IdaGenericBufUnpack,
+    ) -> Result<TILSectionHeader> {
+        let mut header = Self::read_section_header(&mut *input)?;
+        // the ordinal aliases are after the symbols, skip those
+        Self::skip_bucket(&mut *input, &header)?;
+        let (_next_ordinal, type_ordinal_alias) =
+            Self::read_next_ordinal_and_alias(&mut *input, &header)?;
+        header.type_ordinal_alias = type_ordinal_alias;
+        Ok(header)
+    }
+
+    fn read_section_header(
+        input: &mut impl IdaGenericBufUnpack,
+    ) -> Result<TILSectionHeader> {
         let header_raw = Self::read_header(&mut *input)?;
 
         // TODO verify that is always false?
@@ -139,12 +232,15 @@ impl TILSectionRaw {
         } else {
             vec![]
         };
-        let mut header = TILSectionHeader {
+        let (compiler_id, compiler_unsure) =
+            Compiler::from_raw(header_raw.compiler_id);
+        Ok(TILSectionHeader {
             format: header_raw.format,
             description: IDBString::new(header_raw.description),
             flags: header_raw.flags,
             dependencies,
-            compiler_id: Compiler::from_value(header_raw.compiler_id),
+            compiler_id,
+            compiler_unsure,
             cc,
             cn,
             cm,
@@ -156,27 +252,6 @@ impl TILSectionRaw {
             size_enum: header_raw.size_enum,
             extended_sizeof_info: header_raw.extended_sizeof_info,
             type_ordinal_alias: None,
-        };
-
-        let symbols = Self::read_bucket(&mut *input, &header, None)?;
-
-        // TODO create an ordinal -> type mapping, to make sure the ordinals are not duplicated
-        // InnerRef fb47f2c2-3c08-4d40-b7ab-3c7736dce31d 0x42e292
-        let (next_ordinal, type_ordinal_alias) =
-            Self::read_next_ordinal_and_alias(&mut *input, &header)?;
-        header.type_ordinal_alias = type_ordinal_alias;
-        let types = Self::read_bucket(&mut *input, &header, next_ordinal)?;
-        let macros = header
-            .flags
-            .has_macro_table()
-            .then(|| Self::read_macros(&mut *input, &header))
-            .transpose()?;
-
-        Ok(Self {
-            symbols,
-            types,
-            macros,
-            header,
         })
     }
 
@@ -202,7 +277,7 @@ impl TILSectionRaw {
         // TODO verify that no alias cycle exists
         // TODO create and Map for Ord -> Type
         // TODO what is that? Note !0x3Fu8 = 0xC0u8
-        let calc_ord = (next_ord + 0x3f) & 0xffff_ffc0;
+        let calc_ord = next_ord.wrapping_add(0x3f) & 0xffff_ffc0;
 
         // InnerRef fb47f2c2-3c08-4d40-b7ab-3c7736dce31d 0x42e2b6
         ensure!(next_ord <= calc_ord);
@@ -307,8 +382,14 @@ impl TILSectionRaw {
             .transpose()?
             .map(|size| size.try_into())
             .transpose()?;
-        let def_align = (header2.def_align != 0)
-            .then(|| NonZeroU8::new(1 << (header2.def_align - 1)).unwrap());
+        let def_align = match header2.def_align {
+            0 => None,
+            align => Some(
+                1u8.checked_shl((align - 1).into())
+                    .and_then(NonZeroU8::new)
+                    .ok_or_else(|| anyhow!("Invalid default alignment"))?,
+            ),
+        };
 
         Ok(TILSectionHeaderRaw {
             format: header1.format,
@@ -349,27 +430,56 @@ impl TILSectionRaw {
         input: &mut impl IdaGenericBufUnpack,
         header: &TILSectionHeader,
         next_ordinal: Option<u32>,
+        report: &mut ParseReport,
     ) -> Result<Vec<TILTypeInfoRaw>> {
         if header.flags.is_zip() {
-            Self::read_bucket_zip(&mut *input, header, next_ordinal)
+            Self::read_bucket_zip(&mut *input, header, next_ordinal, report)
         } else {
-            Self::read_bucket_normal(&mut *input, header, next_ordinal)
+            Self::read_bucket_normal(&mut *input, header, next_ordinal, report)
         }
     }
 
+    /// consume the bucket data without parsing it
+    fn skip_bucket(
+        input: &mut impl IdaGenericBufUnpack,
+        header: &TILSectionHeader,
+    ) -> Result<()> {
+        let len = if header.flags.is_zip() {
+            let (_ndefs, _len, compressed_len) =
+                Self::read_bucket_zip_header(&mut *input)?;
+            compressed_len
+        } else {
+            let (_ndefs, len) = Self::read_bucket_header(&mut *input)?;
+            len
+        };
+        let skipped =
+            std::io::copy(&mut input.take(len.into()), &mut std::io::sink())?;
+        ensure!(skipped == len.into(), "Unexpected end of TypeBucket data");
+        Ok(())
+    }
+
     fn read_bucket_normal(
         input: &mut impl IdaGenericBufUnpack,
         header: &TILSectionHeader,
         next_ordinal: Option<u32>,
+        report: &mut ParseReport,
     ) -> Result<Vec<TILTypeInfoRaw>> {
         let (ndefs, len) = Self::read_bucket_header(&mut *input)?;
-        Self::read_bucket_inner(&mut *input, header, ndefs, len, next_ordinal)
+        Self::read_bucket_inner(
+            &mut *input,
+            header,
+            ndefs,
+            len,
+            next_ordinal,
+            report,
+        )
     }
 
     fn read_bucket_zip(
         input: &mut impl IdaGenericBufUnpack,
         header: &TILSectionHeader,
         next_ordinal: Option<u32>,
+        report: &mut ParseReport,
     ) -> Result<Vec<TILTypeInfoRaw>> {
         let (ndefs, len, compressed_len) =
             Self::read_bucket_zip_header(&mut *input)?;
@@ -385,12 +495,13 @@ impl TILSectionRaw {
             ndefs,
             len,
             next_ordinal,
+            report,
         )?;
-        #[cfg(feature = "restrictive")]
-        ensure!(
-            compressed_input.limit() == 0,
-            "TypeBucket compressed data is smaller then expected"
-        );
+        report.ensure(compressed_input.limit() == 0, || {
+            Warning::TILUnparsedData(
+                "TypeBucket compressed data is smaller then expected",
+            )
+        })?;
         Ok(type_info)
     }
 
@@ -400,59 +511,61 @@ impl TILSectionRaw {
         ndefs: u32,
         len: u32,
         next_ord: Option<u32>,
+        report: &mut ParseReport,
     ) -> Result<Vec<TILTypeInfoRaw>> {
         if let Some(next_ord) = next_ord {
-            let alias: u32 = header
+            let alias = header
                 .type_ordinal_alias
                 .as_ref()
                 .map(|x| x.len())
-                .unwrap_or(0)
-                .try_into()
-                .unwrap();
+                .unwrap_or(0);
             // InnerRef fb47f2c2-3c08-4d40-b7ab-3c7736dce31d 0x42e3e0
-            ensure!(ndefs + alias + 1 <= next_ord);
+            ensure!(u64::from(ndefs) + alias as u64 + 1 <= next_ord.into());
         }
         let mut input = input.take(len.into());
         let type_info_raw: Vec<_> = (0..ndefs)
             .map(|i| TILTypeInfoRaw::read(&mut input, header, i == ndefs - 1))
             .collect::<Result<_>>()?;
-        #[cfg(feature = "restrictive")]
-        ensure!(
-            input.limit() == 0,
-            "TypeBucket total data is smaller then expected"
-        );
+        report.ensure(input.limit() == 0, || {
+            Warning::TILUnparsedData(
+                "TypeBucket total data is smaller then expected",
+            )
+        })?;
         Ok(type_info_raw)
     }
 
     fn read_macros(
         input: &mut impl IdaGenericBufUnpack,
         header: &TILSectionHeader,
+        report: &mut ParseReport,
     ) -> Result<Vec<TILMacro>> {
         if header.flags.is_zip() {
-            Self::read_macros_zip(&mut *input)
+            Self::read_macros_zip(&mut *input, report)
         } else {
-            Self::read_macros_normal(&mut *input)
+            Self::read_macros_normal(&mut *input, report)
         }
     }
 
     fn read_macros_normal(
         input: &mut impl IdaGenericBufUnpack,
+        report: &mut ParseReport,
     ) -> Result<Vec<TILMacro>> {
         let (ndefs, len) = Self::read_bucket_header(&mut *input)?;
         let mut input = input.take(len.into());
         let type_info = (0..ndefs)
             .map(|_| TILMacro::read(&mut input))
             .collect::<Result<_, _>>()?;
-        #[cfg(feature = "restrictive")]
-        ensure!(
-            input.limit() == 0,
-            "TypeBucket macro total data is smaller then expected"
-        );
+        report.ensure(input.limit() == 0, || {
+            Warning::TILUnparsedData(
+                "TypeBucket macro total data is smaller then expected",
+            )
+        })?;
         Ok(type_info)
     }
 
     fn read_macros_zip(
         input: &mut impl IdaGenericBufUnpack,
+        report: &mut ParseReport,
     ) -> Result<Vec<TILMacro>> {
         let (ndefs, len, compressed_len) =
             Self::read_bucket_zip_header(&mut *input)?;
@@ -467,16 +580,16 @@ impl TILSectionRaw {
             .map(|_| TILMacro::read(&mut decompressed_input))
             .collect::<Result<Vec<_>, _>>()?;
         // make sure the input was fully consumed
-        #[cfg(feature = "restrictive")]
-        ensure!(
-            decompressed_input.limit() == 0,
-            "TypeBucket macros data is smaller then expected"
-        );
-        #[cfg(feature = "restrictive")]
-        ensure!(
-            compressed_input.limit() == 0,
-            "TypeBucket macros compressed data is smaller then expected"
-        );
+        report.ensure(decompressed_input.limit() == 0, || {
+            Warning::TILUnparsedData(
+                "TypeBucket macros data is smaller then expected",
+            )
+        })?;
+        report.ensure(compressed_input.limit() == 0, || {
+            Warning::TILUnparsedData(
+                "TypeBucket macros compressed data is smaller then expected",
+            )
+        })?;
         Ok(type_info)
     }
     // TODO replace usize with a IDTypeIdx type
@@ -623,25 +736,195 @@ impl TILSection {
         self.get_name_idx(name).map(|idx| &self.types[idx])
     }
 
+    /// find the type for the ordinal, following the aliases. None if the
+    /// ordinal is missing or the aliases form a cycle
     pub fn get_ord_idx(&self, id0_ord: Id0TilOrd) -> Option<usize> {
-        // first search the ordinal alias
-        if let Some(ordinals) = &self.header.type_ordinal_alias {
+        let aliases = self.header.type_ordinal_alias.as_deref().unwrap_or(&[]);
+        let mut ord = id0_ord.ord;
+        // without a cycle, there are at most one step per alias
+        for _ in 0..=aliases.len() {
+            // first search the ordinal alias
             // it's unclear what is the first value
-            if let Some((_src, dst)) = ordinals
-                .iter()
-                .find(|(src, _dst)| u64::from(*src) == id0_ord.ord)
-            {
-                return self.get_ord_idx(Id0TilOrd { ord: (*dst).into() });
+            match aliases.iter().find(|(src, _dst)| u64::from(*src) == ord) {
+                Some((_src, dst)) => ord = (*dst).into(),
+                // if not and alias, search for the type directly
+                None => {
+                    return self.types.iter().position(|ty| ty.ordinal == ord)
+                }
             }
         }
-        // if not and alias, search for the type directly
-        self.types.iter().position(|ty| ty.ordinal == id0_ord.ord)
+        None
     }
 
     pub fn get_ord(&self, id0_ord: Id0TilOrd) -> Option<&TILTypeInfo> {
         self.get_ord_idx(id0_ord).map(|idx| &self.types[idx])
     }
 
+    /// all the macros, empty if the section don't have a macro table
+    pub fn macros(&self) -> &[TILMacro] {
+        self.macros.as_deref().unwrap_or(&[])
+    }
+
+    pub fn macro_by_name(&self, name: &[u8]) -> Option<&TILMacro> {
+        self.macros().iter().find(|entry| entry.name == name)
+    }
+
+    /// all the ordinals of the section, including the aliases, sorted by
+    /// ordinal
+    pub fn ordinals(&self) -> impl Iterator<Item = (u64, TILOrdType<'_>)> {
+        let mut ordinals: Vec<_> = self
+            .types
+            .iter()
+            .enumerate()
+            .map(|(idx, ty)| (ty.ordinal, TILOrdType::Type { idx, ty }))
+            .chain(
+                self.header
+                    .type_ordinal_alias
+                    .iter()
+                    .flat_map(|x| x.iter())
+                    .map(|(ord, target)| {
+                        (
+                            u64::from(*ord),
+                            TILOrdType::Alias {
+                                target: (*target).into(),
+                            },
+                        )
+                    }),
+            )
+            .collect();
+        ordinals.sort_by_key(|(ord, _)| *ord);
+        ordinals.into_iter()
+    }
+
+    /// the entry for the ordinal, without following aliases: an alias
+    /// returns [TILOrdType::Alias] with its target ordinal, otherwise the
+    /// type and its index. None if the ordinal is missing
+    pub fn get_by_ordinal(&self, ord: u64) -> Option<TILOrdType<'_>> {
+        if let Some((_ord, target)) = self
+            .header
+            .type_ordinal_alias
+            .iter()
+            .flat_map(|x| x.iter())
+            .find(|(alias, _target)| u64::from(*alias) == ord)
+        {
+            return Some(TILOrdType::Alias {
+                target: (*target).into(),
+            });
+        }
+        self.types
+            .iter()
+            .position(|ty| ty.ordinal == ord)
+            .map(|idx| TILOrdType::Type {
+                idx,
+                ty: &self.types[idx],
+            })
+    }
+
+    /// follow the chain of typedefs, returning the first type that is not
+    /// a typedef, `None` if the chain is broken or loops
+    pub fn resolve_typeref(&self, typeref: &Typeref) -> Option<&TILTypeInfo> {
+        let mut visited = HashSet::new();
+        let mut idx = typeref.resolve_idx(self)?;
+        loop {
+            if !visited.insert(idx) {
+                return None;
+            }
+            let ty = &self.types[idx];
+            let TypeVariant::Typeref(next) = &ty.tinfo.type_variant else {
+                return Some(ty);
+            };
+            idx = next.resolve_idx(self)?;
+        }
+    }
+
+    /// same as [TILSection::resolve_typeref], but names not found in this
+    /// section are searched in `dependencies`, in order
+    pub fn resolve_typeref_with_dependencies<'a>(
+        &'a self,
+        typeref: &Typeref,
+        dependencies: &'a [TILSection],
+    ) -> Option<&'a TILTypeInfo> {
+        let mut visited = HashSet::new();
+        let mut current = typeref;
+        // index 0 is self, the rest are the dependencies
+        let mut section_idx = 0;
+        loop {
+            let section = match section_idx {
+                0 => self,
+                idx => &dependencies[idx - 1],
+            };
+            let (next_section, idx) = match current.resolve_idx(section) {
+                Some(idx) => (section_idx, idx),
+                None => {
+                    let TyperefValue::UnsolvedName(Some(name)) =
+                        &current.typeref_value
+                    else {
+                        return None;
+                    };
+                    dependencies.iter().enumerate().find_map(|(i, dep)| {
+                        dep.get_name_idx(name.as_bytes())
+                            .map(|idx| (i + 1, idx))
+                    })?
+                }
+            };
+            if !visited.insert((next_section, idx)) {
+                return None;
+            }
+            section_idx = next_section;
+            let ty = match section_idx {
+                0 => &self.types[idx],
+                i => &dependencies[i - 1].types[idx],
+            };
+            let TypeVariant::Typeref(next) = &ty.tinfo.type_variant else {
+                return Some(ty);
+            };
+            current = next;
+        }
+    }
+
+    /// load the TIL files this section depends on, searching `search_dirs`
+    /// in order, dependencies of dependencies are also loaded. Missing files
+    /// are ignored, see [TILSection::load_dependencies_with_report]
+    pub fn load_dependencies(
+        &self,
+        search_dirs: &[impl AsRef<Path>],
+    ) -> Result<Vec<TILSection>> {
+        self.load_dependencies_with_report(search_dirs)
+            .map(|(dependencies, _report)| dependencies)
+    }
+
+    /// same as [TILSection::load_dependencies], also reporting the
+    /// dependencies that could not be found
+    pub fn load_dependencies_with_report(
+        &self,
+        search_dirs: &[impl AsRef<Path>],
+    ) -> Result<(Vec<TILSection>, ParseReport)> {
+        let mut report = ParseReport::new();
+        let mut loaded = vec![];
+        let mut visited: HashSet<Vec<u8>> = HashSet::new();
+        let mut pending: Vec<IDBString> =
+            self.header.dependencies.iter().rev().cloned().collect();
+        while let Some(name) = pending.pop() {
+            if !visited.insert(name.as_bytes().to_vec()) {
+                continue;
+            }
+            let Some(path) = find_til_file(&name, search_dirs) else {
+                report.warnings.push(Warning::TILMissingDependency(name));
+                continue;
+            };
+            let mut input = BufReader::new(File::open(&path)?);
+            let section = TILSection::read_with_report(
+                &mut input,
+                IDBSectionCompression::None,
+                &mut report,
+            )
+            .with_context(|| format!("Unable to read {}", path.display()))?;
+            pending.extend(section.header.dependencies.iter().rev().cloned());
+            loaded.push(section);
+        }
+        Ok((loaded, report))
+    }
+
     pub fn sizeof_short(&self) -> NonZeroU8 {
         self.header
             .extended_sizeof_info
@@ -673,14 +956,80 @@ impl TILSection {
             .map(CCPtrSize::near_bytes)
             .unwrap_or(NonZeroU8::new(4).unwrap())
     }
+
+    /// the compiler and all the basic type sizes, including the defaults
+    pub fn compiler_info(&self) -> TILCompilerInfo {
+        TILCompilerInfo {
+            compiler: self.header.compiler_id,
+            ptr_size: self.header.cn,
+            def_align: self.header.def_align,
+            size_bool: self.header.size_bool,
+            size_short: self.sizeof_short(),
+            size_int: self.header.size_int,
+            size_long: self.sizeof_long(),
+            size_long_long: self.sizeof_long_long(),
+            size_enum: self.header.size_enum,
+            size_long_double: self.header.size_long_double,
+        }
+    }
+}
+
+/// find the file for the TIL dependency `name`, with or without extension
+fn find_til_file(
+    name: &IDBString,
+    search_dirs: &[impl AsRef<Path>],
+) -> Option<PathBuf> {
+    let name = name.as_utf8_lossy();
+    let with_ext = format!("{name}.til");
+    search_dirs.iter().find_map(|dir| {
+        [&*name, &with_ext]
+            .into_iter()
+            .map(|file| dir.as_ref().join(file))
+            .find(|path| path.is_file())
+    })
 }
 
 impl TILSection {
+    /// read the TIL section from a IDB file, if any
+    pub fn read_from_idb<R: Read + Seek>(
+        input: R,
+    ) -> Result<Option<TILSection>> {
+        let mut parser = IDBParser::new(BufReader::new(input))?;
+        parser
+            .til_section_offset()
+            .map(|til| parser.read_til_section(til))
+            .transpose()
+    }
+
+    /// read a TIL file from memory, invalid input is always reported as an
+    /// error and never causes a panic, so it's safe to use on untrusted data
+    pub fn try_read_from_slice(data: &[u8]) -> Result<TILSection> {
+        Self::read(&mut &data[..], IDBSectionCompression::None)
+    }
+
     pub fn read(
         input: &mut impl IdaGenericBufUnpack,
         compress: IDBSectionCompression,
     ) -> Result<TILSection> {
-        let type_info_raw = TILSectionRaw::read(input, compress)?;
+        Self::read_with_report(input, compress, &mut ParseReport::new())
+    }
+
+    /// read only the header of the TIL section, without parsing the types,
+    /// symbols and macros
+    pub fn read_header_only(
+        input: &mut impl IdaGenericBufUnpack,
+        compress: IDBSectionCompression,
+    ) -> Result<TILSectionHeader> {
+        TILSectionRaw::read_header_only(input, compress)
+    }
+
+    /// same as [TILSection::read], recording the recovered issues in `report`
+    pub fn read_with_report(
+        input: &mut impl IdaGenericBufUnpack,
+        compress: IDBSectionCompression,
+        report: &mut ParseReport,
+    ) -> Result<TILSection> {
+        let type_info_raw = TILSectionRaw::read(input, compress, report)?;
         // TODO check for dups?
         let type_by_name = type_info_raw
             .types
@@ -698,14 +1047,11 @@ impl TILSection {
             .symbols
             .into_iter()
             .map(|ty| {
-                TILTypeInfo::new(
+                TILTypeInfo::from_raw(
                     &type_info_raw.header,
                     &type_by_name,
                     &type_by_ord,
-                    ty.name,
-                    ty.ordinal,
-                    ty.tinfo,
-                    ty.fields,
+                    ty,
                 )
             })
             .collect::<Result<_>>()?;
@@ -713,14 +1059,11 @@ impl TILSection {
             .types
             .into_iter()
             .map(|ty| {
-                TILTypeInfo::new(
+                TILTypeInfo::from_raw(
                     &type_info_raw.header,
                     &type_by_name,
                     &type_by_ord,
-                    ty.name,
-                    ty.ordinal,
-                    ty.tinfo,
-                    ty.fields,
+                    ty,
                 )
             })
             .collect::<Result<_>>()?;
@@ -732,6 +1075,23 @@ impl TILSection {
             macros: type_info_raw.macros,
         })
     }
+
+    /// all the symbols, in the same order they are stored in the file.
+    ///
+    /// NOTE this order is part of the API, tools that list the symbols by
+    /// name, like `tilib`, depend on it
+    pub fn symbols(&self) -> &[TILTypeInfo] {
+        &self.symbols
+    }
+
+    /// all the types, in the same order they are stored in the file, the
+    /// index in this slice is the one used by [TILSection::get_type_by_idx]
+    ///
+    /// NOTE this order is part of the API, tools that list the types by
+    /// ordinal, like `tilib`, depend on it
+    pub fn types(&self) -> &[TILTypeInfo] {
+        &self.types
+    }
 }
 
 // TODO remove deserialize and implement a verification if the value is correct
@@ -792,6 +1152,27 @@ impl TILSectionFlags {
     pub fn has_size_long_double(&self) -> bool {
         self.0 & flag::til::TIL_SLD != 0
     }
+
+    /// the name of the flags that are set, as printed by tilib
+    ///
+    /// NOTE `TIL_MOD` is not included, it's only relevant for an opened til.
+    pub fn iter_set(&self) -> impl Iterator<Item = &'static str> {
+        const NAMES: [(u16, &str); 8] = [
+            (flag::til::TIL_ZIP, "compressed"),
+            (flag::til::TIL_MAC, "macro_table_present"),
+            (flag::til::TIL_ESI, "extended_sizeof_info"),
+            (flag::til::TIL_UNI, "universal"),
+            (flag::til::TIL_ORD, "ordinals_present"),
+            (flag::til::TIL_ALI, "aliases_present"),
+            (flag::til::TIL_STM, "extra_streams"),
+            (flag::til::TIL_SLD, "sizeof_long_double"),
+        ];
+        let value = self.0;
+        NAMES
+            .into_iter()
+            .filter(move |(flag, _name)| value & flag != 0)
+            .map(|(_flag, name)| name)
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
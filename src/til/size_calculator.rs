@@ -7,7 +7,7 @@ use super::r#enum::Enum;
 use super::r#struct::StructMember;
 use super::section::TILSection;
 use super::union::Union;
-use super::{Basic, Type, TypeVariant, Typeref, TyperefValue};
+use super::{align_mem, Basic, Type, TypeVariant, Typeref, TyperefValue};
 
 pub struct TILTypeSizeSolver<'a> {
     section: &'a TILSection,
@@ -107,11 +107,7 @@ impl<'a> TILTypeSizeSolver<'a> {
                 // TODO default alignment, seems like default alignemnt is the field size
                 let align: u64 = 1;
                 let mut members = &til_struct.members[..];
-                loop {
-                    let Some(first_member) = members.first() else {
-                        // no more members
-                        break;
-                    };
+                while let Some(first_member) = members.first() {
                     let field_size =
                         match &first_member.member_type.type_variant {
                             // if bit-field, condensate one or more to create a byte-field
@@ -145,11 +141,7 @@ impl<'a> TILTypeSizeSolver<'a> {
                             (Some(a), None) | (None, Some(a)) => a,
                             (None, None) => align,
                         };
-                        let align = align.max(1);
-                        let align_diff = sum % align;
-                        if align_diff != 0 {
-                            sum += align - align_diff;
-                        }
+                        sum = align_mem(sum, align);
                     }
                     sum += field_size;
                 }
@@ -185,7 +177,7 @@ impl<'a> TILTypeSizeSolver<'a> {
         }
         let inner_type = self.section.get_type_by_idx(*idx);
         let result = self.inner_type_size_bytes(&inner_type.tinfo);
-        self.solving.remove(&idx);
+        self.solving.remove(idx);
         if let Some(result) = result {
             assert!(self.solved.insert(*idx, result).is_none());
         }
@@ -223,13 +215,10 @@ fn condensate_bitfields_from_struct(
     let field_bits: u16 = u16::from(first_field.nbytes.get()) * 8;
     let mut condensated_bits = first_field.width;
 
-    loop {
-        let Some(TypeVariant::Bitfield(member)) =
-            rest.first().map(|x| &x.member_type.type_variant)
-        else {
-            // no more bit-fields to condensate
-            break;
-        };
+    // condensate bit-fields until a non bit-field member is found
+    while let Some(TypeVariant::Bitfield(member)) =
+        rest.first().map(|x| &x.member_type.type_variant)
+    {
         // condensate the bit-field into the byte-field
         condensated_bits += member.width;
         // check if this bit start the next field
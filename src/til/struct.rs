@@ -1,16 +1,20 @@
 use std::collections::HashMap;
 use std::num::NonZeroU8;
 
+use crate::id0::StrType;
 use crate::ida_reader::IdaGenericBufUnpack;
 use crate::til::{Type, TypeRaw};
 use crate::IDBString;
 use anyhow::{anyhow, ensure, Context, Result};
 use num_enum::{FromPrimitive, IntoPrimitive, TryFromPrimitive};
 
-use super::section::TILSectionHeader;
-use super::{TypeAttribute, TypeVariantRaw};
+use super::section::{TILSection, TILSectionHeader};
+use super::{
+    alignment_to_raw, write_de, write_dt_de, write_ext_att, write_sdacl,
+    TypeAttributes, TypeVariantRaw,
+};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Struct {
     pub effective_alignment: Option<NonZeroU8>,
     pub members: Vec<StructMember>,
@@ -26,6 +30,7 @@ pub struct Struct {
     pub is_uknown_8: bool,
     /// Alignment in bytes
     pub alignment: Option<NonZeroU8>,
+    pub attributes: TypeAttributes,
 }
 impl Struct {
     pub(crate) fn new(
@@ -58,8 +63,59 @@ impl Struct {
             is_vft: value.is_vft,
             is_uknown_8: value.is_unknown_8,
             alignment: value.alignment,
+            attributes: value.attributes,
         })
     }
+
+    pub(crate) fn write<'a>(
+        &'a self,
+        section: &TILSection,
+        output: &mut Vec<u8>,
+        fields: &mut Vec<Option<&'a IDBString>>,
+    ) -> Result<()> {
+        use crate::til::flag::tattr_field::*;
+        use crate::til::flag::tattr_udt::*;
+        use crate::til::flag::tf_complex::*;
+
+        output.push(BT_COMPLEX | BTMT_STRUCT);
+        let mem_cnt = u32::try_from(self.members.len())
+            .ok()
+            .filter(|len| *len < 1 << 29)
+            .ok_or_else(|| anyhow!("Struct with too many members"))?;
+        let alpow = u32::from(alignment_to_raw(self.effective_alignment)?);
+        write_dt_de(output, mem_cnt << 3 | alpow)?;
+
+        // the parser only allow all or none of the members to have att
+        let is_method = self.members.iter().any(|m| m.att.is_some());
+        ensure!(
+            self.members.iter().all(|m| m.att.is_some() == is_method),
+            "Struct with only some members having att"
+        );
+        let mut tattr = alignment_to_raw(self.alignment)?;
+        for (is_set, flag) in [
+            (self.is_uknown_8, 0x8),
+            (self.is_msstruct, TAUDT_MSSTRUCT),
+            (self.is_unaligned, TAUDT_UNALIGNED),
+            (self.is_cppobj, TAUDT_CPPOBJ),
+            (self.is_vft, TAUDT_VFTABLE),
+            (is_method, TAFLD_METHOD),
+        ] {
+            if is_set {
+                tattr |= flag;
+            }
+        }
+        write_sdacl(
+            output,
+            tattr | self.attributes.unknown_flags,
+            &self.attributes.extended,
+        )?;
+
+        for member in &self.members {
+            fields.push(member.name.as_ref());
+            member.write(section, output, fields)?;
+        }
+        Ok(())
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -79,18 +135,20 @@ pub(crate) struct StructRaw {
     is_unknown_8: bool,
     /// Alignment in bytes
     alignment: Option<NonZeroU8>,
+    attributes: TypeAttributes,
 }
 
 impl StructRaw {
-    pub fn read(
+    pub(crate) fn read(
         input: &mut impl IdaGenericBufUnpack,
         header: &TILSectionHeader,
+        depth: usize,
     ) -> Result<TypeVariantRaw> {
         // InnerRef fb47f2c2-3c08-4d40-b7ab-3c7736dce31d 0x459883
         let Some(n) = input.read_dt_de()? else {
             // InnerRef fb47f2c2-3c08-4d40-b7ab-3c7736dce31d 0x4803b4
             // simple reference
-            let ref_type = TypeRaw::read_ref(&mut *input, header)?;
+            let ref_type = TypeRaw::read_ref(&mut *input, header, depth)?;
             let _taudt_bits = input.read_sdacl()?;
             let TypeVariantRaw::Typedef(ref_type) = ref_type.variant else {
                 return Err(anyhow!("StructRef Non Typedef"));
@@ -113,15 +171,13 @@ impl StructRaw {
         let mut is_vft = false;
         let mut is_method = false;
         let mut is_bitset2 = false;
-        if let Some(TypeAttribute {
-            tattr,
-            extended: _extended,
-        }) = input.read_sdacl()?
-        {
+        let mut attributes = TypeAttributes::default();
+        if let Some(attribute) = input.read_sdacl()? {
             use crate::til::flag::tattr::*;
             use crate::til::flag::tattr_field::*;
             use crate::til::flag::tattr_udt::*;
 
+            let tattr = attribute.tattr;
             let align_raw = (tattr & MAX_DECL_ALIGN) as u8;
 
             // TODO WHY?
@@ -140,7 +196,7 @@ impl StructRaw {
             // TODO this value can't be right, it defines the alignment!
             is_bitset2 = align_raw & 0x4 != 0;
 
-            const _ALL_FLAGS: u16 = MAX_DECL_ALIGN
+            const ALL_FLAGS: u16 = MAX_DECL_ALIGN
                 | TAUDT_MSSTRUCT
                 | TAUDT_UNALIGNED
                 | TAUDT_CPPOBJ
@@ -148,14 +204,10 @@ impl StructRaw {
                 | TAFLD_METHOD;
             #[cfg(feature = "restrictive")]
             ensure!(
-                tattr & !_ALL_FLAGS == 0,
+                tattr & !ALL_FLAGS == 0,
                 "Invalid Struct taenum_bits {tattr:x}"
             );
-            #[cfg(feature = "restrictive")]
-            ensure!(
-                _extended.is_none(),
-                "Unable to parse extended attributes for struct"
-            );
+            attributes = TypeAttributes::from_raw(attribute, ALL_FLAGS);
         }
 
         let members = (0..mem_cnt)
@@ -165,6 +217,7 @@ impl StructRaw {
                     header,
                     is_method,
                     is_bitset2,
+                    depth,
                 )
                 .with_context(|| format!("Member {i}"))
             })
@@ -179,11 +232,12 @@ impl StructRaw {
             is_vft,
             is_unknown_8,
             alignment,
+            attributes,
         }))
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct StructMember {
     pub name: Option<IDBString>,
     pub member_type: Type,
@@ -195,6 +249,7 @@ pub struct StructMember {
     pub is_vft: bool,
     pub is_method: bool,
     pub is_unknown_8: bool,
+    pub attributes: TypeAttributes,
 }
 
 impl StructMember {
@@ -222,8 +277,40 @@ impl StructMember {
             is_vft: m.is_vft,
             is_method: m.is_method,
             is_unknown_8: m.is_unknown_8,
+            attributes: m.attributes,
         })
     }
+
+    fn write<'a>(
+        &'a self,
+        section: &TILSection,
+        output: &mut Vec<u8>,
+        fields: &mut Vec<Option<&'a IDBString>>,
+    ) -> Result<()> {
+        use crate::til::flag::tattr_field::*;
+
+        self.member_type.write(section, output, fields)?;
+        if let Some(att) = &self.att {
+            att.write(output)?;
+        }
+        let mut tattr = alignment_to_raw(self.alignment)?;
+        for (is_set, flag) in [
+            (self.is_unknown_8, 0x8),
+            (self.is_baseclass, TAFLD_BASECLASS),
+            (self.is_unaligned, TAFLD_UNALIGNED),
+            (self.is_vft, TAFLD_VFTABLE),
+            (self.is_method, TAFLD_METHOD),
+        ] {
+            if is_set {
+                tattr |= flag;
+            }
+        }
+        write_sdacl(
+            output,
+            tattr | self.attributes.unknown_flags,
+            &self.attributes.extended,
+        )
+    }
 }
 #[derive(Clone, Debug)]
 pub(crate) struct StructMemberRaw {
@@ -235,6 +322,7 @@ pub(crate) struct StructMemberRaw {
     pub is_vft: bool,
     pub is_method: bool,
     pub is_unknown_8: bool,
+    pub attributes: TypeAttributes,
 }
 
 impl StructMemberRaw {
@@ -243,8 +331,9 @@ impl StructMemberRaw {
         header: &TILSectionHeader,
         is_bit_set: bool,
         is_bit_set2: bool,
+        depth: usize,
     ) -> Result<Self> {
-        let ty = TypeRaw::read(&mut *input, header)?;
+        let ty = TypeRaw::read_depth(&mut *input, header, depth)?;
 
         // InnerRef fb47f2c2-3c08-4d40-b7ab-3c7736dce31d 0x478256
         let att = is_bit_set
@@ -257,18 +346,16 @@ impl StructMemberRaw {
         let mut is_vft = false;
         let mut is_method = false;
         let mut is_unknown_8 = false;
+        let mut attributes = TypeAttributes::default();
 
         // InnerRef fb47f2c2-3c08-4d40-b7ab-3c7736dce31d 0x47825d
         if !is_bit_set || att.is_some() {
             // InnerRef fb47f2c2-3c08-4d40-b7ab-3c7736dce31d 0x47825d
-            if let Some(TypeAttribute {
-                tattr,
-                extended: _extended,
-            }) = input.read_sdacl()?
-            {
+            if let Some(attribute) = input.read_sdacl()? {
                 use crate::til::flag::tattr::*;
                 use crate::til::flag::tattr_field::*;
 
+                let tattr = attribute.tattr;
                 let alignment_raw = (tattr & MAX_DECL_ALIGN) as u8;
                 is_unknown_8 = alignment_raw & 0x8 != 0;
                 alignment = ((alignment_raw & 0x7) != 0).then(|| {
@@ -284,21 +371,17 @@ impl StructMemberRaw {
                 is_vft = tattr & TAFLD_VFTABLE != 0;
                 // InnerRef fb47f2c2-3c08-4d40-b7ab-3c7736dce31d 0x478203
                 is_method = tattr & TAFLD_METHOD != 0;
-                const _ALL_FLAGS: u16 = MAX_DECL_ALIGN
+                const ALL_FLAGS: u16 = MAX_DECL_ALIGN
                     | TAFLD_BASECLASS
                     | TAFLD_UNALIGNED
                     | TAFLD_VFTABLE
                     | TAFLD_METHOD;
                 #[cfg(feature = "restrictive")]
                 ensure!(
-                    tattr & !_ALL_FLAGS == 0,
+                    tattr & !ALL_FLAGS == 0,
                     "Invalid Struct taenum_bits {tattr:x}"
                 );
-                #[cfg(feature = "restrictive")]
-                ensure!(
-                    _extended.is_none(),
-                    "Unable to parse extended attributes for struct member"
-                );
+                attributes = TypeAttributes::from_raw(attribute, ALL_FLAGS);
             }
 
             // InnerRef fb47f2c2-3c08-4d40-b7ab-3c7736dce31d 0x47822d
@@ -318,6 +401,7 @@ impl StructMemberRaw {
             is_vft,
             is_method,
             is_unknown_8,
+            attributes,
         })
     }
 
@@ -333,7 +417,7 @@ impl StructMemberRaw {
                 Err(anyhow!("Invalid value for member attribute {att:#x}"))
             }
             0..=7 => Ok(StructMemberAtt::Var0to7(Self::basic_att(input, att)?)),
-            8 | 0xb => todo!(),
+            8 | 0xb => Err(anyhow!("Unsupported member attribute {att:#x}")),
             // InnerRef fb47f2c2-3c08-4d40-b7ab-3c7736dce31d 0x486d3f
             9 => {
                 let val1 = input.read_de()?;
@@ -384,7 +468,7 @@ impl StructMemberRaw {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum StructMemberAtt {
     Var0to7(StructMemberAttBasic),
     Var9 {
@@ -400,7 +484,7 @@ pub enum StructMemberAtt {
 }
 
 // InnerRef InnerRef fb47f2c2-3c08-4d40-b7ab-3c7736dce31d 0x720880
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum StructMemberAttBasic {
     Var1(u64),
     Var2 {
@@ -412,6 +496,44 @@ pub enum StructMemberAttBasic {
 }
 
 impl StructMemberAtt {
+    // the reverse of [StructMemberRaw::read_member_att_1]
+    fn write(&self, output: &mut Vec<u8>) -> Result<()> {
+        match self {
+            Self::Var0to7(basic) => {
+                ensure!(basic.att() & 0xf <= 7, "Invalid member att");
+                write_ext_att(output, basic.att())?;
+                basic.write_values(output)
+            }
+            Self::Var9 {
+                val1,
+                att0,
+                att1,
+                att2,
+            } => {
+                // TODO the upper bits of the att are not kept by the parser
+                write_ext_att(output, 9)?;
+                write_de(output, *val1);
+                match (val1 & 0x1010 == 0, att0) {
+                    (true, Some(att0)) => write_ext_att(output, *att0)?,
+                    (false, None) => {}
+                    _ => return Err(anyhow!("Invalid member att0")),
+                }
+                write_ext_att(output, *att1)?;
+                write_ext_att(output, *att2)
+            }
+            Self::VarAorC { val1, att0 } => {
+                ensure!(
+                    matches!(att0.att() & 0xf, 0xa | 0xc),
+                    "Invalid member att"
+                );
+                write_ext_att(output, att0.att())?;
+                write_de(output, *val1);
+                att0.write_values(output)
+            }
+        }
+    }
+
+    /// the string type of a `__strlit` member, if any
     pub fn str_type(self) -> Option<StringType> {
         match self {
             // 0x8 0xa   "__strlit"
@@ -422,6 +544,7 @@ impl StructMemberAtt {
             _ => None,
         }
     }
+    /// the reference info of a `__offset` pointer member, if any
     pub fn offset_type(self) -> Option<ExtAttOffset> {
         match self {
             // 0x8 0x9   "__offset"
@@ -438,6 +561,7 @@ impl StructMemberAtt {
         }
     }
 
+    /// the number format of the member, eg: `__hex`, if any
     pub fn basic(self) -> Option<ExtAttBasic> {
         match self {
             StructMemberAtt::Var0to7(StructMemberAttBasic::Var1(raw)) => {
@@ -456,7 +580,8 @@ impl StructMemberAtt {
         }
     }
 
-    pub fn basic_offset_type(self) -> Option<(u32, bool)> {
+    /// the reference info of a `__offset` integer member, if any
+    pub fn basic_offset_type(self) -> Option<ExtAttBasicOffset> {
         // TODO find the InnerRef
         match self {
             StructMemberAtt::Var9 {
@@ -464,12 +589,52 @@ impl StructMemberAtt {
                 att0: Some(att0 @ (0 | 0x4e8 | 0x3f58)),
                 att1: 0,
                 att2: u64::MAX,
-            } => Some((val1, att0 != 0)),
+            } => Some(ExtAttBasicOffset {
+                offset: val1,
+                is_auto: att0 != 0,
+            }),
             _ => None,
         }
     }
 }
 
+impl StructMemberAttBasic {
+    fn att(&self) -> u64 {
+        match self {
+            Self::Var1(att) | Self::Var2 { att, .. } => *att,
+        }
+    }
+
+    // the reverse of [StructMemberRaw::basic_att], without the att itself
+    fn write_values(&self, output: &mut Vec<u8>) -> Result<()> {
+        let have_values = (self.att() >> 8) & 0x10 != 0;
+        match self {
+            Self::Var1(_) => {
+                ensure!(!have_values, "Member att is missing values")
+            }
+            Self::Var2 {
+                att: _,
+                val1,
+                val2,
+                val3,
+            } => {
+                ensure!(have_values, "Member att with unexpected values");
+                write_de(output, *val1);
+                write_de(output, *val2);
+                write_de(output, *val3);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ExtAttBasicOffset {
+    pub offset: u32,
+    /// printed as `|AUTO`
+    pub is_auto: bool,
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct ExtAttOffset {
     pub offset: u8,
@@ -478,6 +643,10 @@ pub struct ExtAttOffset {
 }
 
 impl ExtAttOffset {
+    /// the raw `REFINFO_*` flags, without the reference type
+    pub fn flags(&self) -> u32 {
+        self.flag
+    }
     pub fn is_rvaoff(&self) -> bool {
         self.flag & 0x10 != 0
     }
@@ -520,6 +689,11 @@ impl StringType {
     pub fn as_strlib(self) -> u32 {
         self.into()
     }
+
+    /// decode the width, layout and encoding of the raw value
+    pub fn decode(self) -> Option<StrType> {
+        StrType::from_raw(self.as_strlib())
+    }
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -7,10 +7,11 @@ use crate::ida_reader::IdaGenericBufUnpack;
 use crate::til::{Type, TypeRaw};
 use crate::IDBString;
 
-use super::section::TILSectionHeader;
-use super::{TypeAttribute, TypeVariantRaw};
+use super::section::{TILSection, TILSectionHeader};
+use super::{alignment_to_raw, write_dt_de, write_sdacl};
+use super::{TypeAttributes, TypeVariantRaw};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Union {
     pub effective_alignment: u16,
     pub alignment: Option<NonZeroU8>,
@@ -18,6 +19,7 @@ pub struct Union {
 
     pub is_unaligned: bool,
     pub is_unknown_8: bool,
+    pub attributes: TypeAttributes,
 }
 impl Union {
     pub(crate) fn new(
@@ -48,8 +50,57 @@ impl Union {
             members,
             is_unaligned: value.is_unaligned,
             is_unknown_8: value.is_unknown_8,
+            attributes: value.attributes,
         })
     }
+
+    pub(crate) fn write<'a>(
+        &'a self,
+        section: &TILSection,
+        output: &mut Vec<u8>,
+        fields: &mut Vec<Option<&'a IDBString>>,
+    ) -> Result<()> {
+        use crate::til::flag::tattr_udt::*;
+        use crate::til::flag::tf_complex::*;
+
+        output.push(BT_COMPLEX | BTMT_UNION);
+        let mem_cnt = u32::try_from(self.members.len())
+            .ok()
+            .filter(|len| *len < 1 << 29)
+            .ok_or_else(|| anyhow!("Union with too many members"))?;
+        let effective_alignment = match self.effective_alignment {
+            0 => None,
+            align => Some(
+                u8::try_from(align)
+                    .ok()
+                    .and_then(NonZeroU8::new)
+                    .ok_or_else(|| {
+                        anyhow!("Invalid Union alignment {align}")
+                    })?,
+            ),
+        };
+        let alpow = u32::from(alignment_to_raw(effective_alignment)?);
+        write_dt_de(output, mem_cnt << 3 | alpow)?;
+
+        let mut tattr = alignment_to_raw(self.alignment)?;
+        if self.is_unknown_8 {
+            tattr |= 0x8;
+        }
+        if self.is_unaligned {
+            tattr |= TAUDT_UNALIGNED;
+        }
+        write_sdacl(
+            output,
+            tattr | self.attributes.unknown_flags,
+            &self.attributes.extended,
+        )?;
+
+        for (name, member) in &self.members {
+            fields.push(name.as_ref());
+            member.write(section, output, fields)?;
+        }
+        Ok(())
+    }
 }
 
 // TODO struct and union are basically identical, the diff is that member in union don't have SDACL,
@@ -61,17 +112,19 @@ pub(crate) struct UnionRaw {
     members: Vec<TypeRaw>,
     is_unaligned: bool,
     is_unknown_8: bool,
+    attributes: TypeAttributes,
 }
 
 impl UnionRaw {
-    pub fn read(
+    pub(crate) fn read(
         input: &mut impl IdaGenericBufUnpack,
         header: &TILSectionHeader,
+        depth: usize,
     ) -> Result<TypeVariantRaw> {
         let Some(n) = input.read_dt_de()? else {
             // InnerRef fb47f2c2-3c08-4d40-b7ab-3c7736dce31d 0x4803b4
             // is ref
-            let ref_type = TypeRaw::read_ref(&mut *input, header)?;
+            let ref_type = TypeRaw::read_ref(&mut *input, header, depth)?;
             let _taudt_bits = input.read_sdacl()?;
             let TypeVariantRaw::Typedef(ref_type) = ref_type.variant else {
                 return Err(anyhow!("UnionRef Non Typedef"));
@@ -87,14 +140,12 @@ impl UnionRaw {
         let mut alignment = None;
         let mut is_unaligned = false;
         let mut is_unknown_8 = false;
-        if let Some(TypeAttribute {
-            tattr,
-            extended: _extended,
-        }) = input.read_sdacl()?
-        {
+        let mut attributes = TypeAttributes::default();
+        if let Some(attribute) = input.read_sdacl()? {
             use crate::til::flag::tattr::*;
             use crate::til::flag::tattr_udt::*;
 
+            let tattr = attribute.tattr;
             let alignment_raw = (tattr & MAX_DECL_ALIGN) as u8;
             is_unknown_8 = alignment_raw & 0x8 != 0;
             alignment = ((alignment_raw & 0x7) != 0).then(|| {
@@ -102,22 +153,18 @@ impl UnionRaw {
             });
             is_unaligned = tattr & TAUDT_UNALIGNED != 0;
 
-            const _ALL_FLAGS: u16 = MAX_DECL_ALIGN | TAUDT_UNALIGNED;
+            const ALL_FLAGS: u16 = MAX_DECL_ALIGN | TAUDT_UNALIGNED;
             #[cfg(feature = "restrictive")]
             anyhow::ensure!(
-                tattr & !_ALL_FLAGS == 0,
+                tattr & !ALL_FLAGS == 0,
                 "Invalid Union taenum_bits {tattr:x}"
             );
-            #[cfg(feature = "restrictive")]
-            anyhow::ensure!(
-                _extended.is_none(),
-                "Unable to parse extended attributes for union"
-            );
+            attributes = TypeAttributes::from_raw(attribute, ALL_FLAGS);
         }
 
         let members = (0..mem_cnt)
             .map(|i| {
-                TypeRaw::read(&mut *input, header)
+                TypeRaw::read_depth(&mut *input, header, depth)
                     .with_context(|| format!("Member {i}"))
             })
             .collect::<Result<_, _>>()?;
@@ -127,6 +174,7 @@ impl UnionRaw {
             members,
             is_unaligned,
             is_unknown_8,
+            attributes,
         }))
     }
 }
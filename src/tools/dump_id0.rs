@@ -16,7 +16,7 @@ pub fn dump_id0(args: &Args) -> Result<()> {
     Ok(())
 }
 
-fn id0_to_str(input: &[u8]) -> Cow<str> {
+fn id0_to_str(input: &[u8]) -> Cow<'_, str> {
     // ignore any \x00 at the end
     let striped = input.strip_suffix(b"\x00").unwrap_or(input);
     let is_string = striped.iter().all(|b| b.is_ascii_graphic() || *b == b' ');
@@ -0,0 +1,25 @@
+use crate::{print_section, Args, FileType};
+
+use idb_rs::IDBParser;
+
+use std::fs::File;
+use std::io::BufReader;
+
+use anyhow::{anyhow, Result};
+
+pub fn dump_sections(args: &Args) -> Result<()> {
+    let FileType::Idb = args.input_type() else {
+        return Err(anyhow!("TIL files don't contain sections"));
+    };
+    let input = BufReader::new(File::open(&args.input)?);
+    let mut parser = IDBParser::new(input)?;
+    let manifest = parser.section_manifest()?;
+
+    println!("Sections:");
+    print_section("ID0", manifest.id0);
+    print_section("ID1", manifest.id1);
+    print_section("ID2", manifest.id2);
+    print_section("NAM", manifest.nam);
+    print_section("TIL", manifest.til);
+    Ok(())
+}
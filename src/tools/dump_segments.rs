@@ -11,18 +11,9 @@ pub fn dump_segments(args: &Args) -> Result<()> {
         println!("  {:x?}", entry?);
     }
 
-    // TODO create a function for that in ida_info
-    let version = match id0.ida_info()? {
-        idb_rs::id0::IDBParam::V1(idb_rs::id0::IDBParam1 {
-            version, ..
-        }) => version,
-        idb_rs::id0::IDBParam::V2(idb_rs::id0::IDBParam2 {
-            version, ..
-        }) => version,
-    };
     println!();
     println!("Segments AKA `$ fileregions`: ");
-    for entry in id0.file_regions(version)? {
+    for entry in id0.file_regions_auto()? {
         println!("  {:x?}", entry?);
     }
     Ok(())
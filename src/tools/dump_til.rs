@@ -40,6 +40,7 @@ pub fn dump_til(args: &Args) -> Result<()> {
                 description,
                 dependencies,
                 compiler_id,
+                compiler_unsure,
                 cc,
                 cn,
                 cm,
@@ -60,6 +61,7 @@ pub fn dump_til(args: &Args) -> Result<()> {
         println!("dependency-{i}: {}", dependency.as_utf8_lossy());
     }
     println!("id: {compiler_id:?}");
+    println!("id_unsure: {compiler_unsure}");
     println!("cc: {cc:?}");
     println!("cm: {cm:?}");
     println!("cn: {cn:?}");
@@ -0,0 +1,65 @@
+use crate::{print_section, Args, FileType};
+
+use idb_rs::id0::{Compiler, IDBParam, IDBRootInfo};
+use idb_rs::IDBParser;
+
+use std::fs::File;
+use std::io::BufReader;
+
+use anyhow::{anyhow, Result};
+
+pub fn info(args: &Args) -> Result<()> {
+    let FileType::Idb = args.input_type() else {
+        return Err(anyhow!("Only IDB files are supported"));
+    };
+    let input = BufReader::new(File::open(&args.input)?);
+    let mut parser = IDBParser::new(input)?;
+
+    println!("version: {:?}", parser.version());
+    println!("bits: {}", if parser.is_64() { 64 } else { 32 });
+    let manifest = parser.section_manifest()?;
+    println!("sections:");
+    print_section("id0", manifest.id0);
+    print_section("id1", manifest.id1);
+    print_section("id2", manifest.id2);
+    print_section("nam", manifest.nam);
+    print_section("til", manifest.til);
+
+    if let Some(id0_offset) = parser.id0_section_offset() {
+        let id0 = parser.read_id0_section(id0_offset)?;
+        let param = id0.ida_info()?;
+        println!("cpu: {}", param.processor_name());
+        let compiler = match &param {
+            IDBParam::V1(param) => Compiler::from_value(param.compiler),
+            IDBParam::V2(param) => param.cc_id,
+        };
+        println!("compiler: {}", compiler.name());
+        if let Some(path) = id0.input_file_path()? {
+            println!("input_file: {path}");
+        }
+        if let Some(size) = id0.input_file_size()? {
+            println!("input_file_size: {size:#x}");
+        }
+        for entry in id0.root_info()? {
+            match entry? {
+                IDBRootInfo::Md5(md5) => println!("md5: {}", hex(md5)),
+                IDBRootInfo::Sha256(sha256) => {
+                    println!("sha256: {}", hex(sha256))
+                }
+                _ => {}
+            }
+        }
+    }
+
+    if let Some(til_offset) = parser.til_section_offset() {
+        let til = parser.read_til_section(til_offset)?;
+        println!("til_types: {}", til.types.len());
+        println!("til_symbols: {}", til.symbols.len());
+        println!("til_macros: {}", til.macros().len());
+    }
+    Ok(())
+}
+
+fn hex(data: &[u8]) -> String {
+    data.iter().map(|b| format!("{b:02x}")).collect()
+}
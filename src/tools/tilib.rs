@@ -1,20 +1,20 @@
-use idb_rs::id0::{Compiler, Id0TilOrd};
+use idb_rs::id0::Id0TilOrd;
 use idb_rs::til::array::Array;
 use idb_rs::til::bitfield::Bitfield;
 use idb_rs::til::function::{CallingConvention, Function};
 use idb_rs::til::pointer::Pointer;
 use idb_rs::til::r#enum::Enum;
 use idb_rs::til::r#struct::{Struct, StructMemberAtt};
-use idb_rs::til::section::TILSection;
+use idb_rs::til::section::{TILOrdType, TILSection};
 use idb_rs::til::union::Union;
 use idb_rs::til::{
-    Basic, TILTypeInfo, TILTypeSizeSolver, Type, TypeVariant, Typeref,
-    TyperefType, TyperefValue,
+    Basic, TILTypeSizeSolver, Type, TypeVariant, Typeref, TyperefType,
+    TyperefValue,
 };
-use idb_rs::{IDBParser, IDBSectionCompression, IDBString};
+use idb_rs::IDBString;
 
 use std::fs::File;
-use std::io::{BufReader, Result, Write};
+use std::io::{BufRead, BufReader, Result, Write};
 use std::num::NonZeroU8;
 
 use crate::{Args, FileType};
@@ -24,22 +24,29 @@ pub fn tilib_print(args: &Args) -> anyhow::Result<()> {
     let mut input = BufReader::new(File::open(&args.input)?);
     match args.input_type() {
         FileType::Til => {
-            let section =
-                TILSection::read(&mut input, IDBSectionCompression::None)?;
+            let section = read_til_file(&mut input)?;
             print_til_section(std::io::stdout(), &section)?;
         }
         FileType::Idb => {
-            let mut parser = IDBParser::new(input)?;
-            let til_offset = parser.til_section_offset().ok_or_else(|| {
-                anyhow::anyhow!("IDB file don't contains a TIL sector")
-            })?;
-            let section = parser.read_til_section(til_offset)?;
+            let section =
+                TILSection::read_from_idb(input)?.ok_or_else(|| {
+                    anyhow::anyhow!("IDB file don't contains a TIL sector")
+                })?;
             print_til_section(std::io::stdout(), &section)?;
         }
     }
     Ok(())
 }
 
+/// read a standalone TIL file, that can be compressed as a whole
+///
+/// NOTE this is unrelated to the `TIL_ZIP` flag, the compressed buckets are
+/// handled by [TILSection::read] itself.
+fn read_til_file(mut input: impl BufRead) -> anyhow::Result<TILSection> {
+    let compress = idb_rs::detect_compression(input.fill_buf()?)?;
+    TILSection::read(&mut input, compress)
+}
+
 fn print_til_section(mut fmt: impl Write, section: &TILSection) -> Result<()> {
     if !section.header.dependencies.is_empty() {
         // TODO open those files? What todo with then?
@@ -83,7 +90,7 @@ fn print_header(fmt: &mut impl Write, section: &TILSection) -> Result<()> {
     // the description of the file
     // InnerRef fb47f2c2-3c08-4d40-b7ab-3c7736dce31d 0x40b710
     write!(fmt, "Description: ")?;
-    fmt.write_all(&section.header.description.as_bytes())?;
+    fmt.write_all(section.header.description.as_bytes())?;
     writeln!(fmt)?;
 
     // flags from the section header
@@ -104,11 +111,7 @@ fn print_header(fmt: &mut impl Write, section: &TILSection) -> Result<()> {
 
     // compiler name
     // InnerRef fb47f2c2-3c08-4d40-b7ab-3c7736dce31d 0x40b8c5
-    writeln!(
-        fmt,
-        "Compiler   : {}",
-        compiler_id_to_str(section.header.compiler_id)
-    )?;
+    writeln!(fmt, "Compiler   : {}", section.header.compiler_id.name())?;
 
     // alignement and convention stuff
     // InnerRef fb47f2c2-3c08-4d40-b7ab-3c7736dce31d 0x40b7ed
@@ -134,35 +137,32 @@ fn print_header(fmt: &mut impl Write, section: &TILSection) -> Result<()> {
         if section.header.cm.is_some() || section.header.cn.is_some() {
             write!(fmt, ", ")?;
         }
-        write!(fmt, "{}", calling_convention_to_str(cc))?;
+        write!(fmt, "{}", cc.as_str())?;
     }
     writeln!(fmt)?;
 
     // alignment
     // InnerRef fb47f2c2-3c08-4d40-b7ab-3c7736dce31d 0x40b8e4
+    let info = section.compiler_info();
     writeln!(
         fmt,
         "default_align = {} sizeof(bool) = {} sizeof(long)  = {} sizeof(llong) = {}",
-        section.header.def_align.map(|x| x.get()).unwrap_or(0),
-        section.header.size_bool,
-        section.sizeof_long(),
-        section.sizeof_long_long(),
+        info.def_align.map(|x| x.get()).unwrap_or(0),
+        info.size_bool,
+        info.size_long,
+        info.size_long_long,
     )?;
     writeln!(
         fmt,
         "sizeof(enum) = {} sizeof(int) = {} sizeof(short) = {}",
-        section.header.size_enum.map(NonZeroU8::get).unwrap_or(0),
-        section.header.size_int,
-        section.sizeof_short(),
+        info.size_enum.map(NonZeroU8::get).unwrap_or(0),
+        info.size_int,
+        info.size_short,
     )?;
     writeln!(
         fmt,
         "sizeof(long double) = {}",
-        section
-            .header
-            .size_long_double
-            .map(NonZeroU8::get)
-            .unwrap_or(0)
+        info.size_long_double.map(NonZeroU8::get).unwrap_or(0)
     )?;
     Ok(())
 }
@@ -173,52 +173,18 @@ fn print_section_flags(
 ) -> Result<()> {
     let flags = section.header.flags;
     write!(fmt, "Flags      : {:04X}", flags.as_raw())?;
-    if flags.is_zip() {
-        write!(fmt, " compressed")?;
-    }
-    if flags.has_macro_table() {
-        write!(fmt, " macro_table_present")?;
-    }
-    if flags.have_extended_sizeof_info() {
-        write!(fmt, " extended_sizeof_info")?;
-    }
-    if flags.is_universal() {
-        write!(fmt, " universal")?;
-    }
-    if flags.has_ordinal() {
-        write!(fmt, " ordinals_present")?;
-    }
-    if flags.has_type_aliases() {
-        write!(fmt, " aliases_present")?;
-    }
-    if flags.has_extra_stream() {
-        write!(fmt, " extra_streams")?;
-    }
-    if flags.has_size_long_double() {
-        write!(fmt, " sizeof_long_double")?;
+    for name in flags.iter_set() {
+        write!(fmt, " {name}")?;
     }
     writeln!(fmt)
 }
 
-fn compiler_id_to_str(compiler: Compiler) -> &'static str {
-    match compiler {
-        Compiler::Unknown => "Unknown",
-        Compiler::VisualStudio => "Visual C++",
-        Compiler::Borland => "Borland C++",
-        Compiler::Watcom => "Watcom C++",
-        Compiler::Gnu => "GNU C++",
-        Compiler::VisualAge => "Visual Age C++",
-        Compiler::Delphi => "Delphi",
-        Compiler::Other => "?",
-    }
-}
-
 fn print_symbols(
     fmt: &mut impl Write,
     section: &TILSection,
     solver: &mut TILTypeSizeSolver<'_>,
 ) -> Result<()> {
-    for symbol in &section.symbols {
+    for symbol in section.symbols() {
         print_til_type_len(fmt, None, &symbol.tinfo, solver)?;
         let len = solver.type_size_bytes(None, &symbol.tinfo);
         // InnerRef fb47f2c2-3c08-4d40-b7ab-3c7736dce31d 0x409a80
@@ -232,20 +198,8 @@ fn print_symbols(
             _ => write!(fmt, " {:08X}", symbol.ordinal)?,
         }
 
-        // TODO find this in InnerRef fb47f2c2-3c08-4d40-b7ab-3c7736dce31d 0x409a49
-        //let sym_kind = match arg8 {
-        //    0 => "        ",
-        //    1 => "typedef ",
-        //    2 => "extern  ",
-        //    3 => "static  ",
-        //    4 => "register",
-        //    5 => "auto    ",
-        //    6 => "friend  ",
-        //    7 => "virtual ",
-        //    _ => "?!",
-        //};
-        let sym_kind = "        ";
-        write!(fmt, " {} ", sym_kind)?;
+        // InnerRef fb47f2c2-3c08-4d40-b7ab-3c7736dce31d 0x409a49
+        write!(fmt, " {:<8} ", symbol.sclass.name())?;
 
         // TODO investiage this
         let symbol_name = symbol.name.as_bytes();
@@ -292,49 +246,26 @@ fn print_types_by_ordinals(
     section: &TILSection,
     solver: &mut TILTypeSizeSolver<'_>,
 ) -> Result<()> {
-    enum OrdType<'a> {
-        Alias(&'a (u32, u32)),
-        Type { idx: usize, ty: &'a TILTypeInfo },
-    }
-    let mut types_sort: Vec<OrdType> = section
-        .types
-        .iter()
-        .enumerate()
-        .map(|(idx, ty)| OrdType::Type { idx, ty })
-        .chain(
-            section
-                .header
-                .type_ordinal_alias
-                .iter()
-                .flat_map(|x| x.iter())
-                .map(OrdType::Alias),
-        )
-        .collect();
-    types_sort.sort_by_key(|ord| match ord {
-        OrdType::Alias(x) => x.0.into(),
-        OrdType::Type { ty, .. } => ty.ordinal,
-    });
-    for ord_type in types_sort {
-        let ord_num = match ord_type {
-            OrdType::Alias((ord, _)) => (*ord).into(),
-            OrdType::Type { ty, .. } => ty.ordinal,
-        };
+    for (ord_num, ord_type) in section.ordinals() {
         let (idx, final_type) = match ord_type {
-            OrdType::Alias((_alias_ord, type_ord)) => {
+            TILOrdType::Alias { target } => {
                 let idx = section
-                    .get_ord_idx(Id0TilOrd {
-                        ord: (*type_ord).into(),
-                    })
-                    .unwrap();
+                    .get_ord_idx(Id0TilOrd { ord: target })
+                    .ok_or_else(|| {
+                        std::io::Error::other(format!(
+                            "Ordinal {ord_num} is an alias to the missing \
+                            ordinal {target}"
+                        ))
+                    })?;
                 let ty = section.get_type_by_idx(idx);
                 (idx, ty)
             }
-            OrdType::Type { idx, ty } => (idx, ty),
+            TILOrdType::Type { idx, ty } => (idx, ty),
         };
-        print_til_type_len(fmt, Some(idx), &final_type.tinfo, solver).unwrap();
+        print_til_type_len(fmt, Some(idx), &final_type.tinfo, solver)?;
         write!(fmt, "{:5}. ", ord_num)?;
-        if let OrdType::Alias((_alias_ord, type_ord)) = ord_type {
-            write!(fmt, "(aliased to {type_ord}) ")?;
+        if let TILOrdType::Alias { target } = ord_type {
+            write!(fmt, "(aliased to {target}) ")?;
         }
         print_til_type_root(
             fmt,
@@ -352,11 +283,11 @@ fn print_types_by_name(
     section: &TILSection,
     solver: &mut TILTypeSizeSolver<'_>,
 ) -> Result<()> {
-    for (idx, symbol) in section.types.iter().enumerate() {
+    for (idx, symbol) in section.types().iter().enumerate() {
         if symbol.name.as_bytes().is_empty() {
             continue;
         }
-        print_til_type_len(fmt, Some(idx), &symbol.tinfo, solver).unwrap();
+        print_til_type_len(fmt, Some(idx), &symbol.tinfo, solver)?;
         write!(fmt, " ")?;
         print_til_type_root(
             fmt,
@@ -391,6 +322,7 @@ fn print_til_type_root(
     print_til_type(fmt, section, name, til_type, false, true, true, true)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn print_til_type(
     fmt: &mut impl Write,
     section: &TILSection,
@@ -471,6 +403,7 @@ fn print_til_type_basic(
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 fn print_til_type_pointer(
     fmt: &mut impl Write,
     section: &TILSection,
@@ -577,7 +510,7 @@ fn print_til_type_function(
         // if void arg, just don't print the args (there will be none)
         | (_, Some(CallingConvention::Voidarg)) => None,
 
-        (_, Some(cc)) => Some(calling_convention_to_str(cc)),
+        (_, Some(cc)) => Some(cc.as_str()),
     };
 
     // print name and calling convention and some flags
@@ -847,6 +780,7 @@ fn print_til_type_union(
 }
 
 // just print the type, unless we want to embed it
+#[allow(clippy::too_many_arguments)]
 fn print_til_type_complex_member(
     fmt: &mut impl Write,
     section: &TILSection,
@@ -986,7 +920,10 @@ fn print_til_type_enum(
         || til_enum.is_signed
         || til_enum.is_unsigned
     {
-        let bytes = til_enum.storage_size.or(section.header.size_enum).unwrap();
+        let bytes = til_enum
+            .storage_size
+            .or(section.header.size_enum)
+            .ok_or_else(|| std::io::Error::other("Unknown enum size"))?;
         let signed = if til_enum.is_unsigned {
             "unsigned "
         } else {
@@ -994,6 +931,20 @@ fn print_til_type_enum(
         };
         write!(fmt, ": {signed}__int{} ", bytes.get() as usize * 8)?;
     }
+    // values are stored without sign, extend it based on the enum size
+    let value_bytes = til_enum
+        .storage_size
+        .or(section.header.size_enum)
+        .map(NonZeroU8::get)
+        .unwrap_or(4);
+    let sign_extend = |value: u64| -> i64 {
+        match value_bytes {
+            1 => (value as i8).into(),
+            2 => (value as i16).into(),
+            4 => (value as i32).into(),
+            _ => value as i64,
+        }
+    };
     write!(fmt, "{{")?;
     for (member_name, value) in &til_enum.members {
         if let Some(member_name) = member_name {
@@ -1004,10 +955,10 @@ fn print_til_type_enum(
             Char if *value <= 0xFF => {
                 write!(fmt, "'{}'", (*value) as u8 as char)?
             }
-            Char => write!(fmt, "'\\xu{value:X}'")?,
+            Char => write!(fmt, "'\\x{value:02X}'")?,
             Hex => write!(fmt, "{value:#X}")?,
-            SignedDecimal => write!(fmt, "{}", (*value) as i64)?,
-            UnsignedDecimal => write!(fmt, "{value:X}")?,
+            SignedDecimal => write!(fmt, "{}", sign_extend(*value))?,
+            UnsignedDecimal => write!(fmt, "{value}")?,
         }
         // TODO find this in InnerRef
         if let Some(8) = til_enum.storage_size.map(NonZeroU8::get) {
@@ -1057,12 +1008,13 @@ fn print_til_struct_member_att(
             }
             _ => {}
         },
-        TypeVariant::Array(array) => match &array.elem_type.type_variant {
-            TypeVariant::Basic(Basic::Char) => {
+        TypeVariant::Array(array) => {
+            if let TypeVariant::Basic(Basic::Char) =
+                &array.elem_type.type_variant
+            {
                 print_til_struct_member_string_att(fmt, att)?
             }
-            _ => {}
-        },
+        }
         _ => {}
     }
     Ok(())
@@ -1124,11 +1076,12 @@ fn print_til_struct_member_basic_att(
     att: &StructMemberAtt,
 ) -> Result<()> {
     // TODO incomplete implementation
-    if let Some((val, is_auto)) = att.basic_offset_type() {
+    if let Some(offset) = att.basic_offset_type() {
         write!(
             fmt,
-            " __offset({val:#x}{})",
-            if is_auto { "|AUTO" } else { "" }
+            " __offset({:#x}{})",
+            offset.offset,
+            if offset.is_auto { "|AUTO" } else { "" }
         )?;
         return Ok(());
     }
@@ -1230,28 +1183,8 @@ fn print_til_type_len(
     Ok(())
 }
 
-fn calling_convention_to_str(cc: CallingConvention) -> &'static str {
-    use idb_rs::til::function::CallingConvention::*;
-    match cc {
-        Voidarg => "voidarg",
-        Cdecl => "cdecl",
-        Ellipsis => "ellipsis",
-        Stdcall => "stdcall",
-        Pascal => "pascal",
-        Fastcall => "fastcall",
-        Thiscall => "thiscall",
-        Swift => "swift",
-        Golang => "golang",
-        Userpurge => "userpurge",
-        Uservars => "uservars",
-        Usercall => "usercall",
-        Reserved3 => "ccreserved3",
-    }
-}
-
 fn print_macros(fmt: &mut impl Write, section: &TILSection) -> Result<()> {
-    let macro_iter = section.macros.iter().flat_map(Vec::as_slice);
-    for macro_entry in macro_iter {
+    for macro_entry in section.macros() {
         fmt.write_all(&macro_entry.name)?;
         let mut buf = vec![];
         if let Some(param_num) = macro_entry.param_num {
@@ -1278,11 +1211,7 @@ fn print_macros(fmt: &mut impl Write, section: &TILSection) -> Result<()> {
 }
 
 fn print_types_total(fmt: &mut impl Write, section: &TILSection) -> Result<()> {
-    let macros_num = section
-        .macros
-        .as_ref()
-        .map(|macros| macros.len())
-        .unwrap_or(0);
+    let macros_num = section.macros().len();
     let alias_num = section
         .header
         .type_ordinal_alias
@@ -1371,3 +1300,124 @@ fn print_typeref_type_prefix(
         idb_rs::til::TyperefType::Enum => write!(fmt, "enum"),
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use idb_rs::til::r#enum::EnumFormat;
+
+    fn print_enum(output_format: EnumFormat, members: &[u64]) -> String {
+        print_enum_sized(output_format, None, members)
+    }
+
+    fn print_enum_sized(
+        output_format: EnumFormat,
+        storage_size: Option<u8>,
+        members: &[u64],
+    ) -> String {
+        let section = TILSection::read(
+            &mut BufReader::new(File::open("resources/tils/gcc.til").unwrap()),
+            idb_rs::IDBSectionCompression::None,
+        )
+        .unwrap();
+        let til_enum = Enum {
+            is_signed: false,
+            is_unsigned: false,
+            output_format,
+            members: members
+                .iter()
+                .enumerate()
+                .map(|(i, value)| {
+                    let name = format!("M{i}").into_bytes();
+                    (Some(IDBString::new(name)), *value)
+                })
+                .collect(),
+            groups: None,
+            storage_size: storage_size.and_then(NonZeroU8::new),
+            attributes: Default::default(),
+        };
+        let til_type = Type {
+            is_const: false,
+            is_volatile: false,
+            type_variant: TypeVariant::Enum(til_enum.clone()),
+        };
+        let mut output = vec![];
+        print_til_type_enum(
+            &mut output,
+            &section,
+            Some(b"E"),
+            &til_type,
+            &til_enum,
+        )
+        .unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn compressed_til_file() {
+        let data = std::fs::read("resources/tils/gcc.til").unwrap();
+        let mut encoder = flate2::write::ZlibEncoder::new(
+            vec![],
+            flate2::Compression::default(),
+        );
+        encoder.write_all(&data).unwrap();
+        let compressed = encoder.finish().unwrap();
+        let print = |data: &[u8]| {
+            let section = read_til_file(data).unwrap();
+            let mut output = vec![];
+            print_til_section(&mut output, &section).unwrap();
+            output
+        };
+        assert_eq!(print(&compressed), print(&data));
+
+        let zstd_magic = [0x28, 0xB5, 0x2F, 0xFD, 0];
+        let err = read_til_file(&zstd_magic[..]).unwrap_err();
+        assert_eq!(err.to_string(), "Zstd compression is not supported");
+    }
+
+    #[test]
+    fn enum_char_format() {
+        assert_eq!(
+            print_enum(EnumFormat::Char, &[0x41, 0x141, 0x1234]),
+            "enum __char E {M0 = 'A',M1 = '\\x141',M2 = '\\x1234',}"
+        );
+    }
+
+    #[test]
+    fn enum_decimal_format() {
+        assert_eq!(
+            print_enum(EnumFormat::UnsignedDecimal, &[10, 255]),
+            "enum __udec E {M0 = 10,M1 = 255,}"
+        );
+        assert_eq!(
+            print_enum(EnumFormat::SignedDecimal, &[10, 255]),
+            "enum __dec E {M0 = 10,M1 = 255,}"
+        );
+    }
+
+    #[test]
+    fn enum_signed_decimal_sizes() {
+        let values = [0x7F, 0xFF, 0xFFFF, 0xFFFF_FFFF, u64::MAX];
+        let cases = [
+            (1, "__int8", "127,M1 = -1,M2 = -1,M3 = -1,M4 = -1"),
+            (2, "__int16", "127,M1 = 255,M2 = -1,M3 = -1,M4 = -1"),
+            (4, "__int32", "127,M1 = 255,M2 = 65535,M3 = -1,M4 = -1"),
+            (
+                8,
+                "__int64",
+                "127LL,M1 = 255LL,M2 = 65535LL,M3 = 4294967295LL,M4 = -1LL",
+            ),
+        ];
+        for (size, int, members) in cases {
+            let expected = format!("enum __dec E : {int} {{M0 = {members},}}");
+            assert_eq!(
+                print_enum_sized(
+                    EnumFormat::SignedDecimal,
+                    Some(size),
+                    &values
+                ),
+                expected
+            );
+        }
+    }
+}
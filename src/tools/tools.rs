@@ -39,8 +39,12 @@ mod dump_dirtree_bookmarks_tiplace;
 use dump_dirtree_bookmarks_tiplace::dump_dirtree_bookmarks_tiplace;
 mod tilib;
 use tilib::tilib_print;
+mod dump_sections;
+use dump_sections::dump_sections;
+mod info;
+use info::info;
 
-use idb_rs::{id0::ID0Section, IDBParser};
+use idb_rs::{id0::ID0Section, IDBParser, IDBSectionInfo};
 
 use std::fs::File;
 use std::io::BufReader;
@@ -110,6 +114,10 @@ enum Operation {
     DumpDirtreeBookmarksTiplace,
     /// Print all til types from file and it's information
     PrintTilib,
+    /// Dump the sections location and compression, without reading them
+    DumpSections,
+    /// Print a summary of the IDB file
+    Info,
 }
 
 ///// Split the IDB file into it's decompressed sectors. Allow IDB and I64 files.
@@ -156,6 +164,16 @@ fn get_id0_section(args: &Args) -> Result<ID0Section> {
     }
 }
 
+fn print_section(name: &str, info: Option<IDBSectionInfo>) {
+    match info {
+        Some(info) => println!(
+            "  {name}: offset {:#x}, len {:#x}, {:?}",
+            info.offset, info.len, info.compression
+        ),
+        None => println!("  {name}: not present"),
+    }
+}
+
 fn main() -> Result<()> {
     let args = Args::parse();
 
@@ -188,5 +206,7 @@ fn main() -> Result<()> {
             dump_dirtree_bookmarks_tiplace(&args)
         }
         Operation::PrintTilib => tilib_print(&args),
+        Operation::DumpSections => dump_sections(&args),
+        Operation::Info => info(&args),
     }
 }